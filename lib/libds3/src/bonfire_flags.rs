@@ -0,0 +1,116 @@
+//! Event flags set when a bonfire is lit.
+//!
+//! They can't be joined to the rows of `BonfireWarpParam`: a row only holds
+//! the entity ids of the bonfire and its warp point, and the flag is set by
+//! the map's event scripts when the bonfire is lit. So the flags are listed
+//! here, by area and in game order.
+
+#[derive(Debug, Clone, Copy)]
+pub struct BonfireFlag {
+    pub area: &'static str,
+    pub name: &'static str,
+    pub flag: u32,
+}
+
+const fn bonfire(area: &'static str, name: &'static str, flag: u32) -> BonfireFlag {
+    BonfireFlag { area, name, flag }
+}
+
+pub const BONFIRE_FLAGS: &[BonfireFlag] = &[
+    bonfire("Cemetery of Ash", "Cemetery of Ash", 14000002),
+    bonfire("Cemetery of Ash", "Iudex Gundyr", 14000001),
+    bonfire("Firelink Shrine", "Firelink Shrine", 14000000),
+    bonfire("Untended Graves", "Untended Graves", 14000003),
+    bonfire("Untended Graves", "Champion Gundyr", 14000004),
+    bonfire("High Wall of Lothric", "High Wall of Lothric", 13000009),
+    bonfire("High Wall of Lothric", "Tower on the Wall", 13000005),
+    bonfire("High Wall of Lothric", "Vordt of the Boreal Valley", 13000002),
+    bonfire("High Wall of Lothric", "Dancer of the Boreal Valley", 13000004),
+    bonfire("High Wall of Lothric", "Oceiros, the Consumed King", 13000001),
+    bonfire("Undead Settlement", "Foot of the High Wall", 13100004),
+    bonfire("Undead Settlement", "Undead Settlement", 13100000),
+    bonfire("Undead Settlement", "Cliff Underside", 13100002),
+    bonfire("Undead Settlement", "Dilapidated Bridge", 13100003),
+    bonfire("Undead Settlement", "Pit of Hollows", 13100001),
+    bonfire("Road of Sacrifices", "Road of Sacrifices", 13300006),
+    bonfire("Road of Sacrifices", "Halfway Fortress", 13300000),
+    bonfire("Road of Sacrifices", "Crucifixion Woods", 13300007),
+    bonfire("Road of Sacrifices", "Crystal Sage", 13300002),
+    bonfire("Road of Sacrifices", "Farron Keep", 13300003),
+    bonfire("Road of Sacrifices", "Keep Ruins", 13300004),
+    bonfire("Road of Sacrifices", "Farron Keep Perimeter", 13300008),
+    bonfire("Road of Sacrifices", "Old Wolf of Farron", 13300005),
+    bonfire("Road of Sacrifices", "Abyss Watchers", 13300001),
+    bonfire("Cathedral of the Deep", "Cathedral of the Deep", 13500003),
+    bonfire("Cathedral of the Deep", "Cleansing Chapel", 13500000),
+    bonfire("Cathedral of the Deep", "Rosaria's Bed Chamber", 13500002),
+    bonfire("Cathedral of the Deep", "Deacons of the Deep", 13500001),
+    bonfire("Catacombs of Carthus", "Catacombs of Carthus", 13800001),
+    bonfire("Catacombs of Carthus", "High Lord Wolnir", 13800002),
+    bonfire("Catacombs of Carthus", "Abandoned Tomb", 13800003),
+    bonfire("Smouldering Lake", "Demon Ruins", 13800000),
+    bonfire("Smouldering Lake", "Old King's Antechamber", 13800004),
+    bonfire("Smouldering Lake", "Old Demon King", 13800006),
+    bonfire("Irithyll of the Boreal Valley", "Central Irithyll", 13700004),
+    bonfire("Irithyll of the Boreal Valley", "Church of Yorshka", 13700000),
+    bonfire("Irithyll of the Boreal Valley", "Distant Manor", 13700005),
+    bonfire("Irithyll of the Boreal Valley", "Pontiff Sulyvahn", 13700002),
+    bonfire("Irithyll of the Boreal Valley", "Water Reserve", 13700006),
+    bonfire("Anor Londo", "Anor Londo", 13700003),
+    bonfire("Anor Londo", "Prison Tower", 13700008),
+    bonfire("Anor Londo", "Aldrich, Devourer of Gods", 13700001),
+    bonfire("Irithyll Dungeon", "Irithyll Dungeon", 13900000),
+    bonfire("Profaned Capital", "Profaned Capital", 13900002),
+    bonfire("Profaned Capital", "Yhorm the Giant", 13900001),
+    bonfire("Lothric Castle", "Lothric Castle", 13010000),
+    bonfire("Lothric Castle", "Dragon Barracks", 13010002),
+    bonfire("Lothric Castle", "Dragonslayer Armour", 13010001),
+    bonfire("Grand Archives", "Grand Archives", 13410001),
+    bonfire("Grand Archives", "Twin Princes", 13410000),
+    bonfire("Archdragon Peak", "Archdragon Peak", 13200000),
+    bonfire("Archdragon Peak", "Dragon-Kin Mausoleum", 13200002),
+    bonfire("Archdragon Peak", "Great Belfry", 13200003),
+    bonfire("Archdragon Peak", "Nameless King", 13200001),
+    bonfire("Kiln of the First Flame", "Flameless Shrine", 14100000),
+    bonfire("Kiln of the First Flame", "Kiln of the First Flame", 14100001),
+    bonfire("Painted World of Ariandel", "Snowfield", 14500001),
+    bonfire("Painted World of Ariandel", "Rope Bridge Cave", 14500002),
+    bonfire("Painted World of Ariandel", "Corvian Settlement", 14500003),
+    bonfire("Painted World of Ariandel", "Snowy Mountain Pass", 14500004),
+    bonfire("Painted World of Ariandel", "Ariandel Chapel", 14500005),
+    bonfire("Painted World of Ariandel", "Sister Friede", 14500000),
+    bonfire("Painted World of Ariandel", "Depths of the Painting", 14500006),
+    bonfire("Painted World of Ariandel", "Champion's Gravetender", 14500007),
+    bonfire("The Dreg Heap", "The Dreg Heap", 15000000),
+    bonfire("The Dreg Heap", "Earthen Peak Ruins", 15000001),
+    bonfire("The Dreg Heap", "Within the Earthen Peak Ruins", 15000002),
+    bonfire("The Dreg Heap", "The Demon Prince", 15000003),
+    bonfire("The Ringed City", "Mausoleum Lookout", 15100000),
+    bonfire("The Ringed City", "Ringed Inner Wall", 15100001),
+    bonfire("The Ringed City", "Ringed City Streets", 15100002),
+    bonfire("The Ringed City", "Shared Grave", 15100003),
+    bonfire("The Ringed City", "Church of Filianore", 15100004),
+    bonfire("The Ringed City", "Darkeater Midir", 15100005),
+    bonfire("The Ringed City", "Filianore's Rest", 15110000),
+    bonfire("The Ringed City", "Slave Knight Gael", 15110001),
+];
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+
+    #[test]
+    fn test_flags_unique() {
+        let mut flags = HashSet::new();
+        for bonfire in BONFIRE_FLAGS {
+            assert!(
+                flags.insert(bonfire.flag),
+                "{} flag {} is repeated",
+                bonfire.name,
+                bonfire.flag
+            );
+        }
+    }
+}
@@ -0,0 +1,132 @@
+//! The camera the game renders from.
+//!
+//! `CSPersCam` is rebuilt every frame from `ChrExFollowCam`, the camera
+//! following the player. The camera manager holds pointers to both.
+//! Detaching the camera patches out the write of its position, which can
+//! then be moved freely. The rotation and FOV are still written by the game,
+//! so the mouse and right stick keep turning the camera, and the FOV is set
+//! on the follow camera.
+
+use once_cell::sync::Lazy;
+
+use crate::memedit::PointerChain;
+use crate::pointer_chain;
+use crate::scan::{self, FIELD_AREA};
+
+/// `movaps [rbx+40], xmm1`, the write of the camera position.
+static POSITION_UPDATE: Lazy<Option<usize>> =
+    Lazy::new(|| scan::aob_direct(&["0F 29 4B 40 0F 28 ?? ?? ?? ?? ?? 0F 29 ?? 50"]));
+const POSITION_UPDATE_BYTES: [u8; 4] = [0x0F, 0x29, 0x4B, 0x40];
+/// 4 bytes `nop`.
+const NOP: [u8; 4] = [0x0F, 0x1F, 0x40, 0x00];
+
+/// Offset of the camera manager in `FieldArea`.
+const OFFS_CAMERA_MAN: usize = 0x20;
+/// Offset of the pointer to `CSPersCam` in the camera manager.
+const OFFS_PERS_CAM: usize = 0x18;
+/// Offset of the camera matrix in `CSPersCam`.
+const OFFS_MATRIX: usize = 0x10;
+/// Offset of the pointer to `ChrExFollowCam` in the camera manager.
+const OFFS_FOLLOW_CAM: usize = 0x60;
+/// Offset of the vertical FOV, in radians, in `ChrExFollowCam`.
+const OFFS_FOV: usize = 0x50;
+/// Offset of the distance from the player in `ChrExFollowCam`.
+const OFFS_FOLLOW_DISTANCE: usize = 0x1B4;
+/// Offset of the height above the player's origin in `ChrExFollowCam`.
+const OFFS_FOLLOW_HEIGHT: usize = 0x1BC;
+
+/// Rows of the camera's world matrix. The last component of each row is
+/// unused.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[repr(C)]
+pub struct CameraMatrix {
+    pub right: [f32; 4],
+    pub up: [f32; 4],
+    pub forward: [f32; 4],
+    pub position: [f32; 4],
+}
+
+/// The rendered camera. It's attached again when dropped, so that the
+/// patch doesn't outlive the tool.
+#[derive(Debug)]
+pub struct Camera {
+    matrix: PointerChain<CameraMatrix>,
+    position_update: Option<PointerChain<[u8; 4]>>,
+}
+
+impl Camera {
+    /// Returns `None` if `FieldArea` couldn't be found in this game version.
+    pub fn new() -> Option<Self> {
+        let field_area = (*FIELD_AREA)?;
+
+        Some(Camera {
+            matrix: pointer_chain!(field_area, OFFS_CAMERA_MAN, OFFS_PERS_CAM, OFFS_MATRIX),
+            position_update: POSITION_UPDATE.map(|addr| pointer_chain!(addr)),
+        })
+    }
+
+    pub fn matrix(&self) -> Option<CameraMatrix> {
+        self.matrix.read()
+    }
+
+    pub fn set_position(&self, [x, y, z]: [f32; 3]) -> Option<()> {
+        let matrix = self.matrix.read()?;
+        self.matrix.write(CameraMatrix { position: [x, y, z, matrix.position[3]], ..matrix })
+    }
+
+    /// Whether the camera can be detached on this game version.
+    pub fn can_detach(&self) -> bool {
+        self.position_update.is_some()
+    }
+
+    pub fn is_detached(&self) -> bool {
+        self.position_update.as_ref().and_then(|p| p.read()) == Some(NOP)
+    }
+
+    /// Stops or resumes the game's updates of the camera position.
+    pub fn set_detached(&self, detached: bool) -> Option<()> {
+        let position_update = self.position_update.as_ref()?;
+        position_update.write(if detached { NOP } else { POSITION_UPDATE_BYTES })
+    }
+}
+
+impl Drop for Camera {
+    fn drop(&mut self) {
+        if self.is_detached() {
+            self.set_detached(false);
+        }
+    }
+}
+
+/// The camera following the player, which the rendered camera is built
+/// from. Its settings are refreshed by the game when locking on and when
+/// the camera parameters change, so they must be written again to stick.
+#[derive(Debug, Clone)]
+pub struct FollowCamera {
+    pub fov: PointerChain<f32>,
+    pub distance: PointerChain<f32>,
+    pub height: PointerChain<f32>,
+}
+
+impl FollowCamera {
+    /// Returns `None` if `FieldArea` couldn't be found in this game version.
+    pub fn new() -> Option<Self> {
+        let field_area = (*FIELD_AREA)?;
+
+        Some(FollowCamera {
+            fov: pointer_chain!(field_area, OFFS_CAMERA_MAN, OFFS_FOLLOW_CAM, OFFS_FOV),
+            distance: pointer_chain!(
+                field_area,
+                OFFS_CAMERA_MAN,
+                OFFS_FOLLOW_CAM,
+                OFFS_FOLLOW_DISTANCE
+            ),
+            height: pointer_chain!(
+                field_area,
+                OFFS_CAMERA_MAN,
+                OFFS_FOLLOW_CAM,
+                OFFS_FOLLOW_HEIGHT
+            ),
+        })
+    }
+}
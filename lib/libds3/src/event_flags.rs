@@ -0,0 +1,158 @@
+//! Event flags, the game's record of progression: lit bonfires, opened
+//! shortcuts, defeated bosses and so on.
+//!
+//! Flags are bits in `SprjEventFlagMan`, grouped in blocks of 1000. Most
+//! blocks belong to a map area, and where they're stored depends on the
+//! area's world block info in `FieldArea`.
+
+use once_cell::sync::Lazy;
+
+use crate::memedit::{Bitflag, PointerChain};
+use crate::pointer_chain;
+use crate::scan::{self, FIELD_AREA};
+
+static EVENT_FLAG_MAN: Lazy<Option<usize>> = Lazy::new(|| {
+    scan::aob_indirect(
+        &["48 C7 05 ?? ?? ?? ?? 00 00 00 00 48 8B 7C 24 38 C7 46 54 FF FF FF FF 48 83 C4 20 5E C3"],
+        3,
+        11,
+    )
+});
+
+/// Where a flag lives, from the digits of its id: `GAABSNNN` reads as group
+/// `G`, area `AA`, block `B`, sub block `S` and flag `NNN`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct FlagLocation {
+    group: usize,
+    area: u32,
+    block: u32,
+    sub_block: usize,
+    /// Offset of the `u32` holding the flag, in its sub block.
+    word_offset: usize,
+    mask: u32,
+}
+
+impl FlagLocation {
+    fn new(id: u32) -> Self {
+        let flag = id % 1000;
+
+        FlagLocation {
+            group: (id / 10_000_000 % 10) as usize,
+            area: id / 100_000 % 100,
+            block: id / 10_000 % 10,
+            sub_block: (id / 1000 % 10) as usize,
+            word_offset: (flag / 32) as usize * 4,
+            mask: 1 << (31 - flag % 32),
+        }
+    }
+
+    /// Flags that don't belong to a map area.
+    fn is_global(&self) -> bool {
+        self.area >= 90 || self.area + self.block == 0
+    }
+}
+
+fn read<T>(addr: usize) -> Option<T> {
+    PointerChain::<T>::new(&[addr]).read()
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct EventFlags {
+    event_flag_man: usize,
+    field_area: usize,
+}
+
+impl EventFlags {
+    /// Returns `None` if the flag managers couldn't be found in this game
+    /// version.
+    pub fn new() -> Option<Self> {
+        Some(EventFlags { event_flag_man: (*EVENT_FLAG_MAN)?, field_area: (*FIELD_AREA)? })
+    }
+
+    /// Storage category of the flag's block: 0 for global flags, otherwise
+    /// one past the category of the area's world block. Returns `None` when
+    /// the area isn't loaded in the world info.
+    fn category(&self, location: &FlagLocation) -> Option<usize> {
+        if location.is_global() {
+            return Some(0);
+        }
+
+        let world_info_owner: usize = pointer_chain!(self.field_area, 0x10).read()?;
+        let area_count: i32 = read(world_info_owner + 0x8)?;
+
+        (0..area_count.max(0) as usize).map(|i| world_info_owner + 0x10 + i * 0x38).find_map(
+            |area_info| {
+                if u32::from(read::<u8>(area_info + 0xb)?) != location.area {
+                    return None;
+                }
+
+                let block_count: u8 = read(area_info + 0x20)?;
+                let blocks: usize = read(area_info + 0x28)?;
+
+                (0..block_count as usize).map(|j| blocks + j * 0x70).find_map(|block_info| {
+                    let block_id: u32 = read(block_info + 0x8)?;
+                    if (block_id >> 16) & 0xff != location.block || block_id >> 24 != location.area
+                    {
+                        return None;
+                    }
+
+                    let category: i32 = read(block_info + 0x20)?;
+                    (category >= 0).then_some(category as usize + 1)
+                })
+            },
+        )
+    }
+
+    /// The bit of flag `id`. Returns `None` when not in game, or when the
+    /// flag's area isn't loaded.
+    pub fn flag(&self, id: u32) -> Option<Bitflag<u32>> {
+        let location = FlagLocation::new(id);
+        let category = self.category(&location)?;
+
+        let group = PointerChain::<u8>::new(&[self.event_flag_man, 0x218, location.group * 0x18, 0])
+            .eval()? as usize;
+        let sub_block: usize = read(group + (location.sub_block << 4) + category * 0xa8)?;
+        if sub_block == 0 {
+            return None;
+        }
+
+        Some(Bitflag::new(pointer_chain!(sub_block + location.word_offset), location.mask))
+    }
+
+    pub fn get(&self, id: u32) -> Option<bool> {
+        self.flag(id)?.get()
+    }
+
+    pub fn set(&self, id: u32, value: bool) -> Option<()> {
+        let flag = self.flag(id)?;
+        flag.get()?;
+        flag.set(value);
+        Some(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_flag_location() {
+        let location = FlagLocation::new(13_300_006);
+        assert_eq!(location, FlagLocation {
+            group: 1,
+            area: 33,
+            block: 0,
+            sub_block: 0,
+            word_offset: 0,
+            mask: 1 << 25,
+        });
+        assert!(!location.is_global());
+
+        let location = FlagLocation::new(50_002_345);
+        assert_eq!((location.group, location.sub_block), (5, 2));
+        assert_eq!((location.word_offset, location.mask), (40, 1 << 6));
+        assert!(location.is_global());
+
+        assert!(FlagLocation::new(19_500_100).is_global());
+    }
+}
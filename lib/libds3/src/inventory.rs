@@ -0,0 +1,416 @@
+//! The player's inventory and equipped items.
+//!
+//! Both live in `PlayerGameData`, at `[GameDataMan + 0x10]`: the inventory
+//! is a list of item entries, and the equipped items are an array of
+//! inventory indices and item ids, one per equip slot.
+//!
+//! Weapons and armor also have an instance in `SprjGaitemImp`, found by the
+//! entry's handle, which holds their durability.
+//!
+//! Items are equipped through the game's own equip routine, which also
+//! updates what's derived from the equipment: the model, the stats, the
+//! weight and the ring effects. Attuned spells are slots of
+//! `EquipMagicData`, holding `Magic` row ids.
+//!
+//! The offsets are checked against the game's data before anything is
+//! written: see [`Inventory::check_layout`], [`Inventory::durability_of`]
+//! and [`Inventory::attuned_spells`].
+
+use std::mem::size_of;
+
+use once_cell::sync::Lazy;
+
+use crate::memedit::PointerChain;
+use crate::params::Params;
+use crate::{pointer_chain, scan};
+
+static GAITEM_IMP: Lazy<Option<usize>> = Lazy::new(|| {
+    scan::aob_indirect(
+        &["48 8B 0D ?? ?? ?? ?? 8B D3 E8 ?? ?? ?? ?? 48 8B F8 48 85 C0 74 ?? 48 8B 00"],
+        3,
+        7,
+    )
+});
+
+/// `EquipGameData::EquipItem(equip_game_data, slot, inventory_idx)`, which
+/// the equipment menu calls. It starts by checking that the slot is below
+/// 0x15.
+static EQUIP_ITEM: Lazy<Option<usize>> = Lazy::new(|| {
+    scan::aob_direct(&[
+        "48 89 5C 24 ?? 48 89 74 24 ?? 57 48 83 EC 20 41 8B F8 8B F2 48 8B D9 83 FA 15"
+    ])
+});
+
+type FEquipItem = unsafe extern "system" fn(equip_game_data: usize, slot: u32, idx: i32);
+
+/// Offset of the inventory list in `PlayerGameData`.
+const OFFS_INVENTORY: usize = 0x470;
+/// Offset of `EquipGameData` in `PlayerGameData`.
+const OFFS_EQUIP_GAME_DATA: usize = 0x228;
+/// Offset of the equipped inventory indices in `PlayerGameData`. The
+/// equipped item ids follow them.
+const OFFS_EQUIPPED: usize = OFFS_EQUIP_GAME_DATA + 0x34;
+/// Offset of the `EquipMagicData` pointer in `PlayerGameData`, and of the
+/// attunement slots in it.
+const OFFS_EQUIP_MAGIC_DATA: usize = 0x530;
+const OFFS_ATTUNEMENT_SLOTS: usize = 0x18;
+
+pub const ATTUNEMENT_SLOTS: usize = 10;
+
+/// Upper bound on the inventory size, in case the list is read while the
+/// game is setting it up.
+const MAX_ENTRIES: usize = 0x1000;
+
+pub const EQUIP_SLOTS: usize = 22;
+
+/// Offset of the item instances in `SprjGaitemImp`, indexed by the low 16
+/// bits of the inventory entries' handles.
+const OFFS_GAITEM_INS: usize = 0x40;
+const GAITEM_INS_COUNT: usize = 0x1400;
+/// Offset of the durability in an item instance.
+const OFFS_GAITEM_DURABILITY: usize = 0x10;
+
+/// Start of an item instance in `SprjGaitemImp`.
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+struct GaitemIns {
+    _vtable: u64,
+    handle: u32,
+    item_id: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(C)]
+pub struct InventoryEntry {
+    pub handle: u32,
+    pub item_id: u32,
+    pub quantity: u32,
+    _unk: u32,
+}
+
+impl InventoryEntry {
+    /// What the game writes in unused entries.
+    pub const EMPTY: InventoryEntry =
+        InventoryEntry { handle: 0, item_id: u32::MAX, quantity: 0, _unk: 0 };
+
+    pub fn is_empty(&self) -> bool {
+        self.item_id == u32::MAX || self.quantity == 0
+    }
+
+    /// Whether the entry holds goods, which don't have an instance in
+    /// `SprjGaitemImp`.
+    pub fn is_goods(&self) -> bool {
+        !self.is_empty() && self.item_id >> 28 == 4
+    }
+}
+
+/// An attunement slot. Empty slots hold -1.
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+struct AttunementSlot {
+    spell_id: i32,
+    _unk: u32,
+}
+
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+struct InventoryList {
+    entries: u64,
+    capacity: u32,
+}
+
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct Equipped {
+    pub inventory_idx: [i32; EQUIP_SLOTS],
+    pub item_ids: [u32; EQUIP_SLOTS],
+}
+
+/// Equip slots, by index in [`Equipped`]. Slots 10, 11 and 16 are unused.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EquipSlot {
+    LeftHand1 = 0,
+    RightHand1 = 1,
+    LeftHand2 = 2,
+    RightHand2 = 3,
+    LeftHand3 = 4,
+    RightHand3 = 5,
+    Arrow1 = 6,
+    Bolt1 = 7,
+    Arrow2 = 8,
+    Bolt2 = 9,
+    Head = 12,
+    Chest = 13,
+    Hands = 14,
+    Legs = 15,
+    Ring1 = 17,
+    Ring2 = 18,
+    Ring3 = 19,
+    Ring4 = 20,
+    Covenant = 21,
+}
+
+impl EquipSlot {
+    pub const ALL: [EquipSlot; 19] = [
+        EquipSlot::LeftHand1,
+        EquipSlot::RightHand1,
+        EquipSlot::LeftHand2,
+        EquipSlot::RightHand2,
+        EquipSlot::LeftHand3,
+        EquipSlot::RightHand3,
+        EquipSlot::Arrow1,
+        EquipSlot::Bolt1,
+        EquipSlot::Arrow2,
+        EquipSlot::Bolt2,
+        EquipSlot::Head,
+        EquipSlot::Chest,
+        EquipSlot::Hands,
+        EquipSlot::Legs,
+        EquipSlot::Ring1,
+        EquipSlot::Ring2,
+        EquipSlot::Ring3,
+        EquipSlot::Ring4,
+        EquipSlot::Covenant,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            EquipSlot::LeftHand1 => "Left hand 1",
+            EquipSlot::RightHand1 => "Right hand 1",
+            EquipSlot::LeftHand2 => "Left hand 2",
+            EquipSlot::RightHand2 => "Right hand 2",
+            EquipSlot::LeftHand3 => "Left hand 3",
+            EquipSlot::RightHand3 => "Right hand 3",
+            EquipSlot::Arrow1 => "Arrows 1",
+            EquipSlot::Bolt1 => "Bolts 1",
+            EquipSlot::Arrow2 => "Arrows 2",
+            EquipSlot::Bolt2 => "Bolts 2",
+            EquipSlot::Head => "Head",
+            EquipSlot::Chest => "Chest",
+            EquipSlot::Hands => "Hands",
+            EquipSlot::Legs => "Legs",
+            EquipSlot::Ring1 => "Ring 1",
+            EquipSlot::Ring2 => "Ring 2",
+            EquipSlot::Ring3 => "Ring 3",
+            EquipSlot::Ring4 => "Ring 4",
+            EquipSlot::Covenant => "Covenant",
+        }
+    }
+}
+
+impl Equipped {
+    /// The slot the inventory entry `idx` is equipped in, if any.
+    pub fn slot_of(&self, idx: usize) -> Option<EquipSlot> {
+        EquipSlot::ALL.into_iter().find(|&slot| self.inventory_idx[slot as usize] == idx as i32)
+    }
+}
+
+/// An inventory entry, with its index in the inventory list.
+#[derive(Debug, Clone, Copy)]
+pub struct InventoryItem {
+    pub idx: usize,
+    pub entry: InventoryEntry,
+    pub slot: Option<EquipSlot>,
+    /// Only weapons and armor have one.
+    pub durability: Option<u32>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Inventory {
+    list: PointerChain<InventoryList>,
+    equip_game_data: PointerChain<u8>,
+    equipped: PointerChain<Equipped>,
+    attunement: PointerChain<[AttunementSlot; ATTUNEMENT_SLOTS]>,
+}
+
+impl Inventory {
+    pub fn new(base_a: usize) -> Self {
+        Inventory {
+            list: pointer_chain!(base_a, 0x10, OFFS_INVENTORY),
+            equip_game_data: pointer_chain!(base_a, 0x10, OFFS_EQUIP_GAME_DATA),
+            equipped: pointer_chain!(base_a, 0x10, OFFS_EQUIPPED),
+            attunement: pointer_chain!(base_a, 0x10, OFFS_EQUIP_MAGIC_DATA, OFFS_ATTUNEMENT_SLOTS),
+        }
+    }
+
+    fn entry(&self, idx: usize) -> Option<PointerChain<InventoryEntry>> {
+        Inventory::entry_in(&self.list.read()?, idx)
+    }
+
+    fn entry_in(list: &InventoryList, idx: usize) -> Option<PointerChain<InventoryEntry>> {
+        if list.entries == 0 || idx >= (list.capacity as usize).min(MAX_ENTRIES) {
+            return None;
+        }
+
+        Some(pointer_chain!(list.entries as usize + idx * size_of::<InventoryEntry>()))
+    }
+
+    /// Every non-empty entry, with the slot it's equipped in. Returns `None`
+    /// when not in game.
+    pub fn items(&self) -> Option<Vec<InventoryItem>> {
+        let list = self.list.read()?;
+        if list.entries == 0 {
+            return None;
+        }
+
+        let equipped = self.equipped();
+
+        Some(
+            (0..(list.capacity as usize).min(MAX_ENTRIES))
+                .filter_map(|idx| {
+                    let entry = Inventory::entry_in(&list, idx)?.read()?;
+                    let slot = equipped.and_then(|e| e.slot_of(idx));
+                    let durability = Inventory::durability_of(&entry).and_then(|d| d.read());
+                    (!entry.is_empty()).then_some(InventoryItem { idx, entry, slot, durability })
+                })
+                .collect(),
+        )
+    }
+
+    pub fn equipped(&self) -> Option<Equipped> {
+        self.equipped.read()
+    }
+
+    /// Checks the inventory and equipment offsets against the game's data:
+    /// every equipped slot must refer to an inventory entry holding the
+    /// slot's item. Returns `None` when not in game, or when the offsets are
+    /// wrong for this game version, in which case nothing must be written
+    /// through them.
+    pub fn check_layout(&self) -> Option<()> {
+        let list = self.list.read()?;
+        let equipped = self.equipped()?;
+        let mut checked = 0;
+
+        for slot in EquipSlot::ALL {
+            let Ok(idx) = usize::try_from(equipped.inventory_idx[slot as usize]) else {
+                continue;
+            };
+
+            let entry = Inventory::entry_in(&list, idx)?.read()?;
+            if entry.item_id != equipped.item_ids[slot as usize] {
+                return None;
+            }
+            checked += 1;
+        }
+
+        (checked > 0).then_some(())
+    }
+
+    /// The durability of a weapon or armor entry. Returns `None` for other
+    /// items, or when the item's instance doesn't match the entry.
+    fn durability_of(entry: &InventoryEntry) -> Option<PointerChain<u32>> {
+        if entry.is_empty() || entry.item_id >> 28 > 1 {
+            return None;
+        }
+
+        let gaitem_imp = (*GAITEM_IMP)?;
+        let index = (entry.handle & 0xffff) as usize;
+        if index >= GAITEM_INS_COUNT {
+            return None;
+        }
+
+        let ins: u64 =
+            pointer_chain!(gaitem_imp, OFFS_GAITEM_INS + index * size_of::<u64>()).read()?;
+        if ins == 0 {
+            return None;
+        }
+
+        // The instance must be the entry's, which also rules out wrong
+        // offsets.
+        let gaitem: GaitemIns = pointer_chain!(ins as usize).read()?;
+        if gaitem.handle != entry.handle || gaitem.item_id != entry.item_id {
+            return None;
+        }
+
+        Some(pointer_chain!(ins as usize + OFFS_GAITEM_DURABILITY))
+    }
+
+    /// Whether the game's equip routine was found.
+    pub fn can_equip(&self) -> bool {
+        EQUIP_ITEM.is_some()
+    }
+
+    /// Equips the inventory entry `idx` in `slot` through the game's equip
+    /// routine. Returns `None` when the routine wasn't found, or when the
+    /// slot doesn't hold the entry afterwards.
+    pub fn equip(&self, slot: EquipSlot, idx: usize) -> Option<()> {
+        self.check_layout()?;
+        if self.entry(idx)?.read()?.is_empty() {
+            return None;
+        }
+
+        let equip_item: FEquipItem = unsafe { std::mem::transmute((*EQUIP_ITEM)?) };
+        let equip_game_data = self.equip_game_data.eval()? as usize;
+        unsafe { equip_item(equip_game_data, slot as u32, idx as i32) };
+
+        (self.equipped()?.inventory_idx[slot as usize] == idx as i32).then_some(())
+    }
+
+    /// The attuned spells, as `Magic` row ids, with `None` for empty slots.
+    /// Returns `None` when not in game, or when a slot holds something else
+    /// than a spell, e.g. because the offsets are wrong for this game
+    /// version.
+    pub fn attuned_spells(&self, params: &Params) -> Option<[Option<u32>; ATTUNEMENT_SLOTS]> {
+        let slots = self.attunement.read()?;
+        let mut spells = [None; ATTUNEMENT_SLOTS];
+
+        for (spell, slot) in spells.iter_mut().zip(slots) {
+            *spell = match slot.spell_id {
+                -1 => None,
+                id if id > 0 && params.index_of("Magic", id as u64).is_some() => Some(id as u32),
+                _ => return None,
+            };
+        }
+
+        Some(spells)
+    }
+
+    /// Attunes `spells`, `Magic` row ids, in order. The remaining slots are
+    /// emptied.
+    pub fn set_attuned_spells(&self, params: &Params, spells: &[u32]) -> Option<()> {
+        self.attuned_spells(params)?;
+        if spells.len() > ATTUNEMENT_SLOTS
+            || spells.iter().any(|&id| params.index_of("Magic", id as u64).is_none())
+        {
+            return None;
+        }
+
+        let mut slots = self.attunement.read()?;
+        for (idx, slot) in slots.iter_mut().enumerate() {
+            slot.spell_id = spells.get(idx).map(|&id| id as i32).unwrap_or(-1);
+        }
+        self.attunement.write(slots)
+    }
+
+    pub fn set_quantity(&self, idx: usize, quantity: u32) -> Option<()> {
+        self.check_layout()?;
+        let entry = self.entry(idx)?;
+        let value = entry.read()?;
+        entry.write(InventoryEntry { quantity, ..value })
+    }
+
+    pub fn set_durability(&self, idx: usize, durability: u32) -> Option<()> {
+        self.check_layout()?;
+        let entry = self.entry(idx)?.read()?;
+        Inventory::durability_of(&entry)?.write(durability)
+    }
+
+    /// Empties the entry `idx`. Equipped entries are kept.
+    ///
+    /// Only goods can be removed: emptying the entry of another item would
+    /// leave its instance allocated in `SprjGaitemImp`, which the game's
+    /// discard routine releases.
+    pub fn remove(&self, idx: usize) -> Option<()> {
+        self.check_layout()?;
+        if self.equipped()?.slot_of(idx).is_some() {
+            return None;
+        }
+
+        let entry = self.entry(idx)?;
+        if !entry.read()?.is_goods() {
+            return None;
+        }
+
+        entry.write(InventoryEntry::EMPTY)
+    }
+}
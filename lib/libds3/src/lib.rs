@@ -1,16 +1,29 @@
+pub mod bonfire_flags;
+pub mod camera;
 pub mod codegen;
+pub mod event_flags;
+pub mod inventory;
 pub mod memedit;
 pub mod params;
 pub mod pointers;
+pub mod scan;
 pub mod version;
+pub mod warp;
 
 pub mod prelude {
+    pub use crate::bonfire_flags::*;
+    pub use crate::camera::*;
     pub use crate::codegen::*;
+    pub use crate::event_flags::*;
+    pub use crate::inventory::*;
     pub use crate::memedit::*;
     pub use crate::params::*;
     pub use crate::pointers::*;
     pub use crate::version::*;
-    pub use crate::{wait_option, ParamStruct, ParamVisitor};
+    pub use crate::warp::*;
+    pub use crate::{
+        wait_option, FieldMeta, FieldValue, ParamRefVisitor, ParamStruct, ParamVisitor,
+    };
 }
 
 use std::time::Duration;
@@ -24,7 +37,35 @@ pub fn wait_option<T, F: FnMut() -> Option<T>>(mut f: F) -> T {
     }
 }
 
+/// Paramdex metadata of a param field. Most fields are undocumented, so every
+/// item is optional.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct FieldMeta {
+    pub display_name: Option<&'static str>,
+    pub description: Option<&'static str>,
+    /// Key of the field's values in [`params::PARAM_ENUMS`].
+    pub enum_name: Option<&'static str>,
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+}
+
+impl FieldMeta {
+    /// The `(value, name)` pairs of the field's enum, if any.
+    pub fn enum_values(&self) -> Option<&'static [(i64, &'static str)]> {
+        params::PARAM_ENUMS.get(self.enum_name?).copied()
+    }
+
+    /// Looks up an enum value by its name, ignoring case.
+    pub fn enum_value(&self, name: &str) -> Option<i64> {
+        self.enum_values()?.iter().find(|(_, n)| n.eq_ignore_ascii_case(name)).map(|(v, _)| *v)
+    }
+}
+
 pub trait ParamVisitor {
+    /// Called right before the field `name` is visited, for fields that have
+    /// Paramdex metadata.
+    fn visit_meta(&mut self, _name: &str, _meta: &FieldMeta) {}
+
     fn visit_u8(&mut self, name: &str, v: &mut u8);
     fn visit_u16(&mut self, name: &str, v: &mut u16);
     fn visit_u32(&mut self, name: &str, v: &mut u32);
@@ -33,10 +74,94 @@ pub trait ParamVisitor {
     fn visit_i32(&mut self, name: &str, v: &mut i32);
     fn visit_f32(&mut self, name: &str, v: &mut f32);
     fn visit_bool(&mut self, name: &str, v: &mut bool);
+
+    /// Visits bit `bit` of the packed integer field `field`. Visitors that
+    /// don't care about the packing see it as a boolean field named `name`.
+    fn visit_bit(&mut self, name: &str, _field: &str, _bit: u8, v: &mut bool) {
+        self.visit_bool(name, v);
+    }
+}
+
+/// Value of a param field, as read by [`ParamRefVisitor`]. Bits of packed
+/// bit fields are `Bool`s.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FieldValue {
+    U8(u8),
+    U16(u16),
+    U32(u32),
+    I8(i8),
+    I16(i16),
+    I32(i32),
+    F32(f32),
+    Bool(bool),
+}
+
+impl FieldValue {
+    /// The value of integer fields.
+    pub fn as_i64(&self) -> Option<i64> {
+        match *self {
+            FieldValue::U8(v) => Some(v as _),
+            FieldValue::U16(v) => Some(v as _),
+            FieldValue::U32(v) => Some(v as _),
+            FieldValue::I8(v) => Some(v as _),
+            FieldValue::I16(v) => Some(v as _),
+            FieldValue::I32(v) => Some(v as _),
+            FieldValue::F32(_) | FieldValue::Bool(_) => None,
+        }
+    }
+
+    pub fn as_f32(&self) -> Option<f32> {
+        match *self {
+            FieldValue::F32(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match *self {
+            FieldValue::Bool(v) => Some(v),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for FieldValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FieldValue::F32(v) => write!(f, "{v:?}"),
+            FieldValue::Bool(v) => write!(f, "{v}"),
+            v => write!(f, "{}", v.as_i64().unwrap_or_default()),
+        }
+    }
+}
+
+/// Read-only counterpart of [`ParamVisitor`].
+pub trait ParamRefVisitor {
+    /// Called right before the field `name` is visited, for fields that have
+    /// Paramdex metadata.
+    fn visit_meta(&mut self, _name: &str, _meta: &FieldMeta) {}
+
+    fn visit_field(&mut self, name: &'static str, value: FieldValue);
 }
 
 pub trait ParamStruct {
     fn visit<T: ParamVisitor + ?Sized>(&mut self, t: &mut T);
+    fn visit_ref<T: ParamRefVisitor + ?Sized>(&self, t: &mut T);
+
+    /// Every field with its value, in declaration order.
+    fn fields(&self) -> std::vec::IntoIter<(&'static str, FieldValue)> {
+        struct FieldCollector(Vec<(&'static str, FieldValue)>);
+
+        impl ParamRefVisitor for FieldCollector {
+            fn visit_field(&mut self, name: &'static str, value: FieldValue) {
+                self.0.push((name, value));
+            }
+        }
+
+        let mut collector = FieldCollector(Vec::new());
+        self.visit_ref(&mut collector);
+        collector.0.into_iter()
+    }
 }
 
 pub fn print_hex<T: Sized>(ptr: *const T) {
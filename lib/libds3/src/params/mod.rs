@@ -1,4 +1,5 @@
 mod param_data;
+mod regulation;
 use std::collections::{BTreeMap, HashMap};
 use std::ffi::c_void;
 use std::time::Duration;
@@ -8,13 +9,14 @@ use log::{error, info};
 use once_cell::sync::Lazy;
 pub use param_data::*;
 use parking_lot::RwLock;
+pub use regulation::{ParamFile, Regulation};
 use widestring::U16CStr;
 use windows::Win32::System::LibraryLoader::GetModuleHandleA;
 use windows::Win32::System::Memory::{VirtualQuery, MEMORY_BASIC_INFORMATION, PAGE_READWRITE};
 
 use crate::prelude::base_addresses::*;
 use crate::version::VERSION;
-use crate::{wait_option, ParamVisitor};
+use crate::{wait_option, ParamRefVisitor, ParamVisitor};
 
 pub static PARAMS: Lazy<RwLock<Params>> = Lazy::new(|| unsafe {
     wait_option(|| match Params::new() {
@@ -29,6 +31,22 @@ pub static PARAMS: Lazy<RwLock<Params>> = Lazy::new(|| unsafe {
 pub static PARAM_NAMES: Lazy<HashMap<String, HashMap<usize, String>>> =
     Lazy::new(|| serde_json::from_str(include_str!("param_names.json")).unwrap());
 
+/// Visits a zero-initialized row of the given param. This allows inspecting a
+/// param's layout (field names and types) without the game running.
+///
+/// Returns `false` if the param is unknown.
+pub fn visit_param_layout<T: ParamVisitor>(param: &str, visitor: &mut T) -> bool {
+    let (Some(lambda), Some(&size)) = (PARAM_VTABLE.get(param), PARAM_SIZES.get(param)) else {
+        return false;
+    };
+
+    // u64 storage guarantees alignment for every field type.
+    let mut buf = vec![0u64; size.div_ceil(mem::size_of::<u64>())];
+    lambda(buf.as_mut_ptr() as *const c_void, visitor);
+
+    true
+}
+
 #[repr(C)]
 struct ParamMaster {
     unk1: [u64; 2],
@@ -64,13 +82,77 @@ pub struct Param<T: 'static> {
     pub param: Option<&'static mut T>,
 }
 
-pub struct Params(BTreeMap<String, (*const c_void, isize)>);
+type RefreshListener = Box<dyn Fn(&Params) + Send + Sync>;
+
+/// Whether `size` bytes at `addr` are committed, writable memory, as param
+/// memory is. Guards every dereference of the param master, which the game
+/// rewrites while reloading its params.
+unsafe fn is_readable(addr: usize, size: usize) -> bool {
+    let mut memory_basic_info = MEMORY_BASIC_INFORMATION::default();
+
+    if addr == 0
+        || VirtualQuery(
+            Some(addr as _),
+            &mut memory_basic_info,
+            mem::size_of::<MEMORY_BASIC_INFORMATION>(),
+        ) == 0
+    {
+        return false;
+    }
+
+    let region_end =
+        (memory_basic_info.BaseAddress as usize).saturating_add(memory_basic_info.RegionSize);
+
+    memory_basic_info.Protect.contains(PAGE_READWRITE)
+        && addr.checked_add(size).is_some_and(|end| end <= region_end)
+}
+
+/// Reads a pointer at `addr`, if readable.
+unsafe fn read_ptr(addr: usize) -> Option<usize> {
+    is_readable(addr, mem::size_of::<usize>()).then(|| *(addr as *const usize))
+}
+
+/// Upper bound on the number of params, to reject garbage entry arrays.
+const MAX_PARAM_COUNT: isize = 1024;
+
+/// Cheap identity of the params loaded by the game: the bounds of the param
+/// master's entry array, and the row tables of its first and last entries.
+/// Reloading the params reallocates them, which changes the identity.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+struct ParamsIdentity {
+    start: usize,
+    end: usize,
+    first_table: usize,
+    last_table: usize,
+}
+
+pub struct Params {
+    params: BTreeMap<String, (*const c_void, isize)>,
+    /// Row index of each id, by param. Rebuilt at every refresh.
+    id_index: HashMap<String, HashMap<u64, usize>>,
+    /// Incremented at every refresh.
+    generation: u64,
+    /// Identity of the params at the last refresh.
+    identity: ParamsIdentity,
+    /// A changed identity, seen once. The params are only refreshed once it
+    /// has been seen twice in a row, so that a table the game is still
+    /// writing isn't walked.
+    pending_identity: Option<ParamsIdentity>,
+    listeners: Vec<RefreshListener>,
+}
 unsafe impl Send for Params {}
 unsafe impl Sync for Params {}
 
 impl Params {
     unsafe fn new() -> Result<Params, String> {
-        let mut p = Params(BTreeMap::new());
+        let mut p = Params {
+            params: BTreeMap::new(),
+            id_index: HashMap::new(),
+            generation: 0,
+            identity: ParamsIdentity::default(),
+            pending_identity: None,
+            listeners: Vec::new(),
+        };
         p.refresh()?;
 
         Ok(p)
@@ -81,63 +163,151 @@ impl Params {
     /// Accesses raw pointers. Should never crash as the param pointers are
     /// static.
     pub unsafe fn refresh(&mut self) -> Result<(), String> {
-        let addresses: BaseAddresses = (*VERSION).into();
         let module_base_addr = GetModuleHandleA(None).map_err(|e| e.to_string())?.0 as usize;
-        let base_ptr = addresses.param + module_base_addr;
-        let base_ptr = loop {
-            let base_ptr = *(base_ptr as *const *const c_void) as usize;
-            let mut memory_basic_info = MEMORY_BASIC_INFORMATION::default();
-
-            VirtualQuery(
-                Some(base_ptr as _),
-                &mut memory_basic_info,
-                mem::size_of::<MEMORY_BASIC_INFORMATION>(),
-            );
-
-            if memory_basic_info.Protect.contains(PAGE_READWRITE) {
-                break base_ptr;
+        let (base, identity) = loop {
+            if let Some(base) = Params::read_master(module_base_addr) {
+                if let Some(identity) = Params::identity(base) {
+                    break (base, identity);
+                }
             }
             thread::sleep(Duration::from_millis(500));
         };
 
-        let base: &ParamMaster = (base_ptr as *const ParamMaster) // std::ptr::read(base_ptr as *const *const ParamMaster)
-            .as_ref()
-            .ok_or_else(|| "Invalid param base address".to_string())?;
-
         let m = Params::param_entries_from_master(base)?;
-        self.0 = m;
+        self.params = m;
+        self.identity = identity;
+        self.pending_identity = None;
+        self.build_id_index();
+        self.generation += 1;
+
+        for listener in &self.listeners {
+            listener(self);
+        }
+
         Ok(())
     }
 
+    /// Checks that the cached param pointers still match the game's param
+    /// master, and refreshes them if they don't. The game reloads its params
+    /// on regulation updates, which leaves the cached pointers dangling.
+    ///
+    /// Only the params' identity is compared, which doesn't walk the
+    /// entries. A changed identity must be seen by two consecutive checks
+    /// before refreshing, so nothing is done while the params are being
+    /// reloaded. Returns `true` if the params were refreshed.
+    ///
+    /// # Safety
+    ///
+    /// Accesses raw pointers.
+    pub unsafe fn ensure_fresh(&mut self) -> Result<bool, String> {
+        let module_base_addr = GetModuleHandleA(None).map_err(|e| e.to_string())?.0 as usize;
+        let Some(identity) =
+            Params::read_master(module_base_addr).and_then(|base| Params::identity(base))
+        else {
+            return Ok(false);
+        };
+
+        if identity == self.identity {
+            self.pending_identity = None;
+            return Ok(false);
+        }
+
+        if self.pending_identity.replace(identity) != Some(identity) {
+            return Ok(false);
+        }
+
+        info!("Params were reloaded, refreshing");
+        self.refresh()?;
+        Ok(true)
+    }
+
+    /// Registers a function that is called after every refresh, including the
+    /// ones caused by the game reloading its params. Edits to param rows are
+    /// lost on reload, and can be reapplied from here.
+    pub fn on_refresh(&mut self, listener: impl Fn(&Params) + Send + Sync + 'static) {
+        self.listeners.push(Box::new(listener));
+    }
+
+    /// Number of refreshes so far. A change means row references obtained
+    /// before are stale.
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// Returns the param master, or `None` if its memory isn't readable yet.
+    unsafe fn read_master(module_base_addr: usize) -> Option<&'static ParamMaster> {
+        let addresses: BaseAddresses = (*VERSION).into();
+        let base_ptr = *((addresses.param + module_base_addr) as *const *const c_void) as usize;
+
+        if is_readable(base_ptr, mem::size_of::<ParamMaster>()) {
+            (base_ptr as *const ParamMaster).as_ref()
+        } else {
+            None
+        }
+    }
+
+    /// The param master's entries, or `None` if the array isn't sane and
+    /// readable.
+    unsafe fn param_entries(base: &ParamMaster) -> Option<&'static [*const ParamEntry]> {
+        let (start, end) = (base.start as usize, base.end as usize);
+        let count = (end.checked_sub(start)? / mem::size_of::<usize>()) as isize;
+
+        if count == 0 || count > MAX_PARAM_COUNT || !is_readable(start, end - start) {
+            return None;
+        }
+
+        Some(std::slice::from_raw_parts(base.start, count as usize))
+    }
+
+    /// The row table of a param entry, if every pointer on the way is
+    /// readable.
+    unsafe fn param_table(entry: *const ParamEntry) -> Option<usize> {
+        let ptr = read_ptr(entry as usize + 0x68)?;
+        let table = read_ptr(ptr + 0x68)?;
+        is_readable(table, 0x10).then_some(table)
+    }
+
+    unsafe fn identity(base: &ParamMaster) -> Option<ParamsIdentity> {
+        let entries = Params::param_entries(base)?;
+
+        Some(ParamsIdentity {
+            start: base.start as usize,
+            end: base.end as usize,
+            first_table: Params::param_table(*entries.first()?)?,
+            last_table: Params::param_table(*entries.last()?)?,
+        })
+    }
+
     unsafe fn param_entries_from_master(
         base: &ParamMaster,
     ) -> Result<BTreeMap<String, (*const c_void, isize)>, String> {
-        let count = base.end.offset_from(base.start);
-
-        let param_entries: &[*const ParamEntry] =
-            std::slice::from_raw_parts(base.start, count as usize);
+        let param_entries = Params::param_entries(base)
+            .ok_or_else(|| format!("Invalid param entries {:p}..{:p}", base.start, base.end))?;
 
         let m = param_entries
             .iter()
             .map(|&param_ptr| {
-                let e = param_ptr.as_ref().ok_or_else(|| format!("Wrong ptr {:p}", param_ptr))?;
+                if !is_readable(param_ptr as usize, mem::size_of::<ParamEntry>()) {
+                    return Err(format!("Wrong ptr {:p}", param_ptr));
+                }
+                let e = &*param_ptr;
                 let ustr = U16CStr::from_slice_truncate(if e.param_length <= 7 {
                     &e.param_name.direct
                 } else {
-                    e.param_name
-                        .indirect
-                        .as_ref()
-                        .ok_or_else(|| format!("Wrong string ptr: {:p}", e.param_name.indirect))?
+                    let indirect = e.param_name.indirect;
+                    if !is_readable(indirect as usize, mem::size_of::<[u16; 90]>()) {
+                        return Err(format!("Wrong string ptr: {:p}", indirect));
+                    }
+                    &*indirect
                 });
                 let name = ustr
                     .map_err(|e| format!("{}", e))?
                     .to_string()
                     .map_err(|e| format!("{}", e))?;
 
-                let ptr = param_ptr as *const c_void;
-                let ptr = *(ptr.offset(0x68) as *const *const c_void);
-                let ptr = *(ptr.offset(0x68) as *const *const c_void);
-                let count = *(ptr.offset(0x0a) as *const u16);
+                let ptr = Params::param_table(param_ptr)
+                    .ok_or_else(|| format!("Wrong table ptr for {name}"))?;
+                let count = *((ptr + 0x0a) as *const u16);
 
                 Ok((name, (ptr as _, count as isize)))
             })
@@ -153,12 +323,38 @@ impl Params {
         Ok(m)
     }
 
+    unsafe fn build_id_index(&mut self) {
+        self.id_index = self
+            .params
+            .keys()
+            .filter_map(|name| {
+                let ids = self.iter_param_ids(name)?;
+                Some((name.clone(), ids.enumerate().map(|(idx, id)| (id, idx)).collect()))
+            })
+            .collect();
+    }
+
     pub fn keys(&self) -> impl Iterator<Item = &String> {
-        self.0.keys()
+        self.params.keys()
     }
 
     fn get_param_ptr(&self, s: &str) -> Option<(*const c_void, isize)> {
-        self.0.get(s).cloned()
+        self.params.get(s).cloned()
+    }
+
+    /// Row index of the row `id` of `param`, looked up in the index built at
+    /// the last refresh.
+    pub fn index_of(&self, param: &str, id: u64) -> Option<usize> {
+        self.id_index.get(param)?.get(&id).copied()
+    }
+
+    /// # Safety
+    ///
+    /// Accesses raw pointers. Ensure that the param is properly initialized
+    /// (e.g. with the params well-formed and loaded into memory) before
+    /// invoking, and that `T` is the param's struct.
+    pub unsafe fn get_by_id<T: 'static>(&self, param: &str, id: u64) -> Option<Param<T>> {
+        self.get_param_by_idx(param, self.index_of(param, id)?)
     }
 
     pub fn visit_param_item<T: ParamVisitor>(
@@ -174,6 +370,20 @@ impl Params {
         }
     }
 
+    /// Like [`Params::visit_param_item`], without write access to the row.
+    pub fn visit_param_item_ref<T: ParamRefVisitor>(
+        &self,
+        param: &str,
+        param_idx: usize,
+        visitor: &mut T,
+    ) {
+        if let Some((lambda, ptr)) = PARAM_REF_VTABLE.get(param).and_then(|lambda| {
+            unsafe { self.get_param_idx_ptr(param, param_idx) }.map(|v| (lambda, v))
+        }) {
+            lambda(ptr, visitor);
+        }
+    }
+
     /// # Safety
     ///
     /// Accesses raw pointers. Ensure that the param is properly initialized
@@ -231,7 +441,7 @@ impl Params {
     /// Accesses raw pointers. Ensure that the param is properly initialized
     /// (e.g. with the params well-formed and loaded into memory) before
     /// invoking.
-    unsafe fn _get_param_idx<T: 'static>(&self, s: &str, i: usize) -> Option<Param<T>> {
+    unsafe fn get_param_by_idx<T: 'static>(&self, s: &str, i: usize) -> Option<Param<T>> {
         let (param_ptr, count) = self.get_param_ptr(s)?;
 
         if i >= (count as usize) {
@@ -17,6 +17,14 @@ unsafe fn get_lambda<T: ParamStruct>() -> BoxedVisitorLambda {
     })
 }
 
+unsafe fn get_ref_lambda<T: ParamStruct>() -> BoxedRefVisitorLambda {
+    Box::new(|ptr, v| {
+        if let Some(r) = (ptr as *const T).as_ref() {
+            r.visit_ref(&mut *v);
+        }
+    })
+}
+
 type BoxedVisitorLambda = Box<dyn Fn(*const c_void, &mut dyn ParamVisitor) + Send + Sync>;
 
 pub static PARAM_VTABLE: Lazy<HashMap<String, BoxedVisitorLambda>> = Lazy::new(|| {
@@ -145,6 +153,275 @@ pub static PARAM_VTABLE: Lazy<HashMap<String, BoxedVisitorLambda>> = Lazy::new(|
     .into_iter()
     .collect()
 });
+type BoxedRefVisitorLambda = Box<dyn Fn(*const c_void, &mut dyn ParamRefVisitor) + Send + Sync>;
+
+pub static PARAM_REF_VTABLE: Lazy<HashMap<String, BoxedRefVisitorLambda>> = Lazy::new(|| {
+    [
+        ("ActionButtonParam".to_string(), unsafe { get_ref_lambda::<ActionButtonParam>() }),
+        ("AiSoundParam".to_string(), unsafe { get_ref_lambda::<AiSoundParam>() }),
+        ("AtkParam_Npc".to_string(), unsafe { get_ref_lambda::<AtkParam_Npc>() }),
+        ("AtkParam_Pc".to_string(), unsafe { get_ref_lambda::<AtkParam_Pc>() }),
+        ("AttackElementCorrectParam".to_string(), unsafe {
+            get_ref_lambda::<AttackElementCorrectParam>()
+        }),
+        ("BehaviorParam".to_string(), unsafe { get_ref_lambda::<BehaviorParam>() }),
+        ("BehaviorParam_PC".to_string(), unsafe { get_ref_lambda::<BehaviorParam_PC>() }),
+        ("BonfireWarpParam".to_string(), unsafe { get_ref_lambda::<BonfireWarpParam>() }),
+        ("BudgetParam".to_string(), unsafe { get_ref_lambda::<BudgetParam>() }),
+        ("Bullet".to_string(), unsafe { get_ref_lambda::<Bullet>() }),
+        ("BulletCreateLimitParam".to_string(), unsafe {
+            get_ref_lambda::<BulletCreateLimitParam>()
+        }),
+        ("CalcCorrectGraph".to_string(), unsafe { get_ref_lambda::<CalcCorrectGraph>() }),
+        ("Ceremony".to_string(), unsafe { get_ref_lambda::<Ceremony>() }),
+        ("CharacterLoadParam".to_string(), unsafe { get_ref_lambda::<CharacterLoadParam>() }),
+        ("CharaInitParam".to_string(), unsafe { get_ref_lambda::<CharaInitParam>() }),
+        ("CharMakeMenuListItemParam".to_string(), unsafe {
+            get_ref_lambda::<CharMakeMenuListItemParam>()
+        }),
+        ("CharMakeMenuTopParam".to_string(), unsafe { get_ref_lambda::<CharMakeMenuTopParam>() }),
+        ("ClearCountCorrectParam".to_string(), unsafe {
+            get_ref_lambda::<ClearCountCorrectParam>()
+        }),
+        ("CoolTimeParam".to_string(), unsafe { get_ref_lambda::<CoolTimeParam>() }),
+        ("CultSettingParam".to_string(), unsafe { get_ref_lambda::<CultSettingParam>() }),
+        ("DecalParam".to_string(), unsafe { get_ref_lambda::<DecalParam>() }),
+        ("DirectionCameraParam".to_string(), unsafe { get_ref_lambda::<DirectionCameraParam>() }),
+        ("EquipMtrlSetParam".to_string(), unsafe { get_ref_lambda::<EquipMtrlSetParam>() }),
+        ("EquipParamAccessory".to_string(), unsafe { get_ref_lambda::<EquipParamAccessory>() }),
+        ("EquipParamGoods".to_string(), unsafe { get_ref_lambda::<EquipParamGoods>() }),
+        ("EquipParamProtector".to_string(), unsafe { get_ref_lambda::<EquipParamProtector>() }),
+        ("EquipParamWeapon".to_string(), unsafe { get_ref_lambda::<EquipParamWeapon>() }),
+        ("FaceGenParam".to_string(), unsafe { get_ref_lambda::<FaceGenParam>() }),
+        ("FaceParam".to_string(), unsafe { get_ref_lambda::<FaceParam>() }),
+        ("FaceRangeParam".to_string(), unsafe { get_ref_lambda::<FaceRangeParam>() }),
+        ("FootSfxParam".to_string(), unsafe { get_ref_lambda::<FootSfxParam>() }),
+        ("GameAreaParam".to_string(), unsafe { get_ref_lambda::<GameAreaParam>() }),
+        ("GameProgressParam".to_string(), unsafe { get_ref_lambda::<GameProgressParam>() }),
+        ("GemCategoryParam".to_string(), unsafe { get_ref_lambda::<GemCategoryParam>() }),
+        ("GemDropDopingParam".to_string(), unsafe { get_ref_lambda::<GemDropDopingParam>() }),
+        ("GemDropModifyParam".to_string(), unsafe { get_ref_lambda::<GemDropModifyParam>() }),
+        ("GemeffectParam".to_string(), unsafe { get_ref_lambda::<GemeffectParam>() }),
+        ("GemGenParam".to_string(), unsafe { get_ref_lambda::<GemGenParam>() }),
+        ("HitEffectSeParam".to_string(), unsafe { get_ref_lambda::<HitEffectSeParam>() }),
+        ("HitEffectSfxConceptParam".to_string(), unsafe {
+            get_ref_lambda::<HitEffectSfxConceptParam>()
+        }),
+        ("HitEffectSfxParam".to_string(), unsafe { get_ref_lambda::<HitEffectSfxParam>() }),
+        ("HitMtrlParam".to_string(), unsafe { get_ref_lambda::<HitMtrlParam>() }),
+        ("HPEstusFlaskRecoveryParam".to_string(), unsafe {
+            get_ref_lambda::<HPEstusFlaskRecoveryParam>()
+        }),
+        ("ItemLotParam".to_string(), unsafe { get_ref_lambda::<ItemLotParam>() }),
+        ("KnockBackParam".to_string(), unsafe { get_ref_lambda::<KnockBackParam>() }),
+        ("KnowledgeLoadScreenItemParam".to_string(), unsafe {
+            get_ref_lambda::<KnowledgeLoadScreenItemParam>()
+        }),
+        ("LoadBalancerDrawDistScaleParam".to_string(), unsafe {
+            get_ref_lambda::<LoadBalancerDrawDistScaleParam>()
+        }),
+        ("LoadBalancerParam".to_string(), unsafe { get_ref_lambda::<LoadBalancerParam>() }),
+        ("LockCamParam".to_string(), unsafe { get_ref_lambda::<LockCamParam>() }),
+        ("LodParam".to_string(), unsafe { get_ref_lambda::<LodParam>() }),
+        ("LodParam_ps4".to_string(), unsafe { get_ref_lambda::<LodParam_ps4>() }),
+        ("LodParam_xb1".to_string(), unsafe { get_ref_lambda::<LodParam_xb1>() }),
+        ("Magic".to_string(), unsafe { get_ref_lambda::<Magic>() }),
+        ("MapMimicryEstablishmentParam".to_string(), unsafe {
+            get_ref_lambda::<MapMimicryEstablishmentParam>()
+        }),
+        ("MenuOffscrRendParam".to_string(), unsafe { get_ref_lambda::<MenuOffscrRendParam>() }),
+        ("MenuPropertyLayoutParam".to_string(), unsafe {
+            get_ref_lambda::<MenuPropertyLayoutParam>()
+        }),
+        ("MenuPropertySpecParam".to_string(), unsafe { get_ref_lambda::<MenuPropertySpecParam>() }),
+        ("MenuValueTableParam".to_string(), unsafe { get_ref_lambda::<MenuValueTableParam>() }),
+        ("ModelSfxParam".to_string(), unsafe { get_ref_lambda::<ModelSfxParam>() }),
+        ("MoveParam".to_string(), unsafe { get_ref_lambda::<MoveParam>() }),
+        ("MPEstusFlaskRecoveryParam".to_string(), unsafe {
+            get_ref_lambda::<MPEstusFlaskRecoveryParam>()
+        }),
+        ("MultiHPEstusFlaskBonusParam".to_string(), unsafe {
+            get_ref_lambda::<MultiHPEstusFlaskBonusParam>()
+        }),
+        ("MultiMPEstusFlaskBonusParam".to_string(), unsafe {
+            get_ref_lambda::<MultiMPEstusFlaskBonusParam>()
+        }),
+        ("MultiPlayCorrectionParam".to_string(), unsafe {
+            get_ref_lambda::<MultiPlayCorrectionParam>()
+        }),
+        ("MultiSoulBonusRateParam".to_string(), unsafe {
+            get_ref_lambda::<MultiSoulBonusRateParam>()
+        }),
+        ("NetworkAreaParam".to_string(), unsafe { get_ref_lambda::<NetworkAreaParam>() }),
+        ("NetworkMsgParam".to_string(), unsafe { get_ref_lambda::<NetworkMsgParam>() }),
+        ("NetworkParam".to_string(), unsafe { get_ref_lambda::<NetworkParam>() }),
+        ("NewMenuColorTableParam".to_string(), unsafe {
+            get_ref_lambda::<NewMenuColorTableParam>()
+        }),
+        ("NpcAiActionParam".to_string(), unsafe { get_ref_lambda::<NpcAiActionParam>() }),
+        ("NpcParam".to_string(), unsafe { get_ref_lambda::<NpcParam>() }),
+        ("NpcThinkParam".to_string(), unsafe { get_ref_lambda::<NpcThinkParam>() }),
+        ("ObjActParam".to_string(), unsafe { get_ref_lambda::<ObjActParam>() }),
+        ("ObjectMaterialSfxParam".to_string(), unsafe {
+            get_ref_lambda::<ObjectMaterialSfxParam>()
+        }),
+        ("ObjectParam".to_string(), unsafe { get_ref_lambda::<ObjectParam>() }),
+        ("PhantomParam".to_string(), unsafe { get_ref_lambda::<PhantomParam>() }),
+        ("PlayRegionParam".to_string(), unsafe { get_ref_lambda::<PlayRegionParam>() }),
+        ("ProtectorGenParam".to_string(), unsafe { get_ref_lambda::<ProtectorGenParam>() }),
+        ("RagdollParam".to_string(), unsafe { get_ref_lambda::<RagdollParam>() }),
+        ("ReinforceParamProtector".to_string(), unsafe {
+            get_ref_lambda::<ReinforceParamProtector>()
+        }),
+        ("ReinforceParamWeapon".to_string(), unsafe { get_ref_lambda::<ReinforceParamWeapon>() }),
+        ("RoleParam".to_string(), unsafe { get_ref_lambda::<RoleParam>() }),
+        ("SeMaterialConvertParam".to_string(), unsafe {
+            get_ref_lambda::<SeMaterialConvertParam>()
+        }),
+        ("ShopLineupParam".to_string(), unsafe { get_ref_lambda::<ShopLineupParam>() }),
+        ("SkeletonParam".to_string(), unsafe { get_ref_lambda::<SkeletonParam>() }),
+        ("SpEffectParam".to_string(), unsafe { get_ref_lambda::<SpEffectParam>() }),
+        ("SpEffectVfxParam".to_string(), unsafe { get_ref_lambda::<SpEffectVfxParam>() }),
+        ("SwordArtsParam".to_string(), unsafe { get_ref_lambda::<SwordArtsParam>() }),
+        ("TalkParam".to_string(), unsafe { get_ref_lambda::<TalkParam>() }),
+        ("ThrowDirectionSfxParam".to_string(), unsafe {
+            get_ref_lambda::<ThrowDirectionSfxParam>()
+        }),
+        ("ThrowParam".to_string(), unsafe { get_ref_lambda::<ThrowParam>() }),
+        ("ToughnessParam".to_string(), unsafe { get_ref_lambda::<ToughnessParam>() }),
+        ("UpperArmParam".to_string(), unsafe { get_ref_lambda::<UpperArmParam>() }),
+        ("WeaponGenParam".to_string(), unsafe { get_ref_lambda::<WeaponGenParam>() }),
+        ("WepAbsorpPosParam".to_string(), unsafe { get_ref_lambda::<WepAbsorpPosParam>() }),
+        ("WetAspectParam".to_string(), unsafe { get_ref_lambda::<WetAspectParam>() }),
+        ("WhiteSignCoolTimeParam".to_string(), unsafe {
+            get_ref_lambda::<WhiteSignCoolTimeParam>()
+        }),
+        ("Wind".to_string(), unsafe { get_ref_lambda::<Wind>() }),
+    ]
+    .into_iter()
+    .collect()
+});
+pub static PARAM_SIZES: Lazy<HashMap<String, usize>> = Lazy::new(|| {
+    [
+        ("ActionButtonParam".to_string(), std::mem::size_of::<ActionButtonParam>()),
+        ("AiSoundParam".to_string(), std::mem::size_of::<AiSoundParam>()),
+        ("AtkParam_Npc".to_string(), std::mem::size_of::<AtkParam_Npc>()),
+        ("AtkParam_Pc".to_string(), std::mem::size_of::<AtkParam_Pc>()),
+        ("AttackElementCorrectParam".to_string(), std::mem::size_of::<AttackElementCorrectParam>()),
+        ("BehaviorParam".to_string(), std::mem::size_of::<BehaviorParam>()),
+        ("BehaviorParam_PC".to_string(), std::mem::size_of::<BehaviorParam_PC>()),
+        ("BonfireWarpParam".to_string(), std::mem::size_of::<BonfireWarpParam>()),
+        ("BudgetParam".to_string(), std::mem::size_of::<BudgetParam>()),
+        ("Bullet".to_string(), std::mem::size_of::<Bullet>()),
+        ("BulletCreateLimitParam".to_string(), std::mem::size_of::<BulletCreateLimitParam>()),
+        ("CalcCorrectGraph".to_string(), std::mem::size_of::<CalcCorrectGraph>()),
+        ("Ceremony".to_string(), std::mem::size_of::<Ceremony>()),
+        ("CharacterLoadParam".to_string(), std::mem::size_of::<CharacterLoadParam>()),
+        ("CharaInitParam".to_string(), std::mem::size_of::<CharaInitParam>()),
+        ("CharMakeMenuListItemParam".to_string(), std::mem::size_of::<CharMakeMenuListItemParam>()),
+        ("CharMakeMenuTopParam".to_string(), std::mem::size_of::<CharMakeMenuTopParam>()),
+        ("ClearCountCorrectParam".to_string(), std::mem::size_of::<ClearCountCorrectParam>()),
+        ("CoolTimeParam".to_string(), std::mem::size_of::<CoolTimeParam>()),
+        ("CultSettingParam".to_string(), std::mem::size_of::<CultSettingParam>()),
+        ("DecalParam".to_string(), std::mem::size_of::<DecalParam>()),
+        ("DirectionCameraParam".to_string(), std::mem::size_of::<DirectionCameraParam>()),
+        ("EquipMtrlSetParam".to_string(), std::mem::size_of::<EquipMtrlSetParam>()),
+        ("EquipParamAccessory".to_string(), std::mem::size_of::<EquipParamAccessory>()),
+        ("EquipParamGoods".to_string(), std::mem::size_of::<EquipParamGoods>()),
+        ("EquipParamProtector".to_string(), std::mem::size_of::<EquipParamProtector>()),
+        ("EquipParamWeapon".to_string(), std::mem::size_of::<EquipParamWeapon>()),
+        ("FaceGenParam".to_string(), std::mem::size_of::<FaceGenParam>()),
+        ("FaceParam".to_string(), std::mem::size_of::<FaceParam>()),
+        ("FaceRangeParam".to_string(), std::mem::size_of::<FaceRangeParam>()),
+        ("FootSfxParam".to_string(), std::mem::size_of::<FootSfxParam>()),
+        ("GameAreaParam".to_string(), std::mem::size_of::<GameAreaParam>()),
+        ("GameProgressParam".to_string(), std::mem::size_of::<GameProgressParam>()),
+        ("GemCategoryParam".to_string(), std::mem::size_of::<GemCategoryParam>()),
+        ("GemDropDopingParam".to_string(), std::mem::size_of::<GemDropDopingParam>()),
+        ("GemDropModifyParam".to_string(), std::mem::size_of::<GemDropModifyParam>()),
+        ("GemeffectParam".to_string(), std::mem::size_of::<GemeffectParam>()),
+        ("GemGenParam".to_string(), std::mem::size_of::<GemGenParam>()),
+        ("HitEffectSeParam".to_string(), std::mem::size_of::<HitEffectSeParam>()),
+        ("HitEffectSfxConceptParam".to_string(), std::mem::size_of::<HitEffectSfxConceptParam>()),
+        ("HitEffectSfxParam".to_string(), std::mem::size_of::<HitEffectSfxParam>()),
+        ("HitMtrlParam".to_string(), std::mem::size_of::<HitMtrlParam>()),
+        ("HPEstusFlaskRecoveryParam".to_string(), std::mem::size_of::<HPEstusFlaskRecoveryParam>()),
+        ("ItemLotParam".to_string(), std::mem::size_of::<ItemLotParam>()),
+        ("KnockBackParam".to_string(), std::mem::size_of::<KnockBackParam>()),
+        (
+            "KnowledgeLoadScreenItemParam".to_string(),
+            std::mem::size_of::<KnowledgeLoadScreenItemParam>(),
+        ),
+        (
+            "LoadBalancerDrawDistScaleParam".to_string(),
+            std::mem::size_of::<LoadBalancerDrawDistScaleParam>(),
+        ),
+        ("LoadBalancerParam".to_string(), std::mem::size_of::<LoadBalancerParam>()),
+        ("LockCamParam".to_string(), std::mem::size_of::<LockCamParam>()),
+        ("LodParam".to_string(), std::mem::size_of::<LodParam>()),
+        ("LodParam_ps4".to_string(), std::mem::size_of::<LodParam_ps4>()),
+        ("LodParam_xb1".to_string(), std::mem::size_of::<LodParam_xb1>()),
+        ("Magic".to_string(), std::mem::size_of::<Magic>()),
+        (
+            "MapMimicryEstablishmentParam".to_string(),
+            std::mem::size_of::<MapMimicryEstablishmentParam>(),
+        ),
+        ("MenuOffscrRendParam".to_string(), std::mem::size_of::<MenuOffscrRendParam>()),
+        ("MenuPropertyLayoutParam".to_string(), std::mem::size_of::<MenuPropertyLayoutParam>()),
+        ("MenuPropertySpecParam".to_string(), std::mem::size_of::<MenuPropertySpecParam>()),
+        ("MenuValueTableParam".to_string(), std::mem::size_of::<MenuValueTableParam>()),
+        ("ModelSfxParam".to_string(), std::mem::size_of::<ModelSfxParam>()),
+        ("MoveParam".to_string(), std::mem::size_of::<MoveParam>()),
+        ("MPEstusFlaskRecoveryParam".to_string(), std::mem::size_of::<MPEstusFlaskRecoveryParam>()),
+        (
+            "MultiHPEstusFlaskBonusParam".to_string(),
+            std::mem::size_of::<MultiHPEstusFlaskBonusParam>(),
+        ),
+        (
+            "MultiMPEstusFlaskBonusParam".to_string(),
+            std::mem::size_of::<MultiMPEstusFlaskBonusParam>(),
+        ),
+        ("MultiPlayCorrectionParam".to_string(), std::mem::size_of::<MultiPlayCorrectionParam>()),
+        ("MultiSoulBonusRateParam".to_string(), std::mem::size_of::<MultiSoulBonusRateParam>()),
+        ("NetworkAreaParam".to_string(), std::mem::size_of::<NetworkAreaParam>()),
+        ("NetworkMsgParam".to_string(), std::mem::size_of::<NetworkMsgParam>()),
+        ("NetworkParam".to_string(), std::mem::size_of::<NetworkParam>()),
+        ("NewMenuColorTableParam".to_string(), std::mem::size_of::<NewMenuColorTableParam>()),
+        ("NpcAiActionParam".to_string(), std::mem::size_of::<NpcAiActionParam>()),
+        ("NpcParam".to_string(), std::mem::size_of::<NpcParam>()),
+        ("NpcThinkParam".to_string(), std::mem::size_of::<NpcThinkParam>()),
+        ("ObjActParam".to_string(), std::mem::size_of::<ObjActParam>()),
+        ("ObjectMaterialSfxParam".to_string(), std::mem::size_of::<ObjectMaterialSfxParam>()),
+        ("ObjectParam".to_string(), std::mem::size_of::<ObjectParam>()),
+        ("PhantomParam".to_string(), std::mem::size_of::<PhantomParam>()),
+        ("PlayRegionParam".to_string(), std::mem::size_of::<PlayRegionParam>()),
+        ("ProtectorGenParam".to_string(), std::mem::size_of::<ProtectorGenParam>()),
+        ("RagdollParam".to_string(), std::mem::size_of::<RagdollParam>()),
+        ("ReinforceParamProtector".to_string(), std::mem::size_of::<ReinforceParamProtector>()),
+        ("ReinforceParamWeapon".to_string(), std::mem::size_of::<ReinforceParamWeapon>()),
+        ("RoleParam".to_string(), std::mem::size_of::<RoleParam>()),
+        ("SeMaterialConvertParam".to_string(), std::mem::size_of::<SeMaterialConvertParam>()),
+        ("ShopLineupParam".to_string(), std::mem::size_of::<ShopLineupParam>()),
+        ("SkeletonParam".to_string(), std::mem::size_of::<SkeletonParam>()),
+        ("SpEffectParam".to_string(), std::mem::size_of::<SpEffectParam>()),
+        ("SpEffectVfxParam".to_string(), std::mem::size_of::<SpEffectVfxParam>()),
+        ("SwordArtsParam".to_string(), std::mem::size_of::<SwordArtsParam>()),
+        ("TalkParam".to_string(), std::mem::size_of::<TalkParam>()),
+        ("ThrowDirectionSfxParam".to_string(), std::mem::size_of::<ThrowDirectionSfxParam>()),
+        ("ThrowParam".to_string(), std::mem::size_of::<ThrowParam>()),
+        ("ToughnessParam".to_string(), std::mem::size_of::<ToughnessParam>()),
+        ("UpperArmParam".to_string(), std::mem::size_of::<UpperArmParam>()),
+        ("WeaponGenParam".to_string(), std::mem::size_of::<WeaponGenParam>()),
+        ("WepAbsorpPosParam".to_string(), std::mem::size_of::<WepAbsorpPosParam>()),
+        ("WetAspectParam".to_string(), std::mem::size_of::<WetAspectParam>()),
+        ("WhiteSignCoolTimeParam".to_string(), std::mem::size_of::<WhiteSignCoolTimeParam>()),
+        ("Wind".to_string(), std::mem::size_of::<Wind>()),
+    ]
+    .into_iter()
+    .collect()
+});
+pub static PARAM_ENUMS: Lazy<HashMap<&'static str, &'static [(i64, &'static str)]>> =
+    Lazy::new(|| [].into_iter().collect());
 #[derive(ParamStruct, Debug)]
 #[repr(C)]
 pub struct ActionButtonParam {
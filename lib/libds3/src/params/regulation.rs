@@ -0,0 +1,540 @@
+//! Offline param reader.
+//!
+//! Reads the BND4 archive of PARAM files found in a decrypted and
+//! decompressed `regulation.bin` (e.g. as unpacked by Yapped or WitchyBND).
+//! Decryption and DCX decompression are not handled here.
+//!
+//! The game loads PARAM files into memory as they are, so rows have the same
+//! layout as the structs in `param_data.rs`.
+
+use std::collections::BTreeMap;
+use std::mem;
+use std::path::Path;
+
+use log::error;
+
+use super::{PARAM_REF_VTABLE, PARAM_SIZES};
+use crate::{ParamRefVisitor, ParamStruct};
+
+const PARAM_HEADER_SIZE_SHORT: usize = 0x30;
+const PARAM_HEADER_SIZE: usize = 0x40;
+
+// PARAM format flags (byte 0x2D).
+const PARAM_FLAG_01: u8 = 0x01;
+const PARAM_INT_DATA_OFFSET: u8 = 0x02;
+const PARAM_LONG_DATA_OFFSET: u8 = 0x04;
+const PARAM_OFFSET_PARAM_TYPE: u8 = 0x80;
+
+// PARAM format flags (byte 0x2E).
+const PARAM_UNICODE_NAMES: u8 = 0x01;
+
+// BND4 format flags.
+const BND4_IDS: u8 = 0x02;
+const BND4_NAMES1: u8 = 0x04;
+const BND4_NAMES2: u8 = 0x08;
+const BND4_LONG_OFFSETS: u8 = 0x10;
+const BND4_COMPRESSION: u8 = 0x20;
+
+/// `base + index * stride`, failing instead of overflowing on offsets read
+/// from malformed files.
+fn offset_of(base: usize, index: usize, stride: usize) -> Result<usize, String> {
+    index
+        .checked_mul(stride)
+        .and_then(|o| o.checked_add(base))
+        .ok_or_else(|| format!("Offset {base:#x} + {index:#x} * {stride:#x} overflows"))
+}
+
+fn read<const N: usize>(data: &[u8], offset: usize) -> Result<[u8; N], String> {
+    offset
+        .checked_add(N)
+        .and_then(|end| data.get(offset..end))
+        .and_then(|s| s.try_into().ok())
+        .ok_or_else(|| format!("Unexpected end of data at {offset:#x}"))
+}
+
+fn read_u8(data: &[u8], offset: usize) -> Result<u8, String> {
+    read::<1>(data, offset).map(|[b]| b)
+}
+
+fn read_u16(data: &[u8], offset: usize) -> Result<u16, String> {
+    read(data, offset).map(u16::from_le_bytes)
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Result<u32, String> {
+    read(data, offset).map(u32::from_le_bytes)
+}
+
+fn read_u64(data: &[u8], offset: usize) -> Result<u64, String> {
+    read(data, offset).map(u64::from_le_bytes)
+}
+
+/// Reads a null-terminated string, either ASCII or UTF-16.
+fn read_str(data: &[u8], offset: usize, unicode: bool) -> Result<String, String> {
+    let data = data.get(offset..).ok_or_else(|| format!("Invalid string offset {offset:#x}"))?;
+
+    if unicode {
+        let chars = data
+            .chunks_exact(2)
+            .map(|c| u16::from_le_bytes([c[0], c[1]]))
+            .take_while(|&c| c != 0)
+            .collect::<Vec<_>>();
+        String::from_utf16(&chars).map_err(|e| e.to_string())
+    } else {
+        let len = data.iter().position(|&b| b == 0).unwrap_or(data.len());
+        Ok(String::from_utf8_lossy(&data[..len]).into_owned())
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct ParamFileRow {
+    id: u64,
+    data_offset: usize,
+    name_offset: usize,
+}
+
+/// A single PARAM file.
+pub struct ParamFile {
+    data: Vec<u8>,
+    param_type: String,
+    unicode: bool,
+    rows: Vec<ParamFileRow>,
+    row_size: usize,
+}
+
+impl ParamFile {
+    pub fn parse(data: Vec<u8>) -> Result<ParamFile, String> {
+        if read_u8(&data, 0x2C)? != 0 {
+            return Err("Big endian params are not supported".to_string());
+        }
+
+        let format_2d = read_u8(&data, 0x2D)?;
+        let format_2e = read_u8(&data, 0x2E)?;
+        let strings_offset = read_u32(&data, 0x00)? as usize;
+        let row_count = read_u16(&data, 0x0A)? as usize;
+
+        let param_type = if format_2d & PARAM_OFFSET_PARAM_TYPE != 0 {
+            read_str(&data, read_u64(&data, 0x10)? as usize, false)?
+        } else {
+            read_str(&data[..0x2C], 0x0C, false)?
+        };
+
+        let long_offsets = format_2d & PARAM_LONG_DATA_OFFSET != 0;
+        let rows_offset = if (format_2d & PARAM_FLAG_01 != 0
+            && format_2d & PARAM_INT_DATA_OFFSET != 0)
+            || long_offsets
+        {
+            PARAM_HEADER_SIZE
+        } else {
+            PARAM_HEADER_SIZE_SHORT
+        };
+
+        let rows = (0..row_count)
+            .map(|i| {
+                if long_offsets {
+                    let offset = offset_of(rows_offset, i, 0x18)?;
+                    Ok(ParamFileRow {
+                        id: read_u32(&data, offset)? as u64,
+                        data_offset: read_u64(&data, offset_of(offset, 1, 0x08)?)? as usize,
+                        name_offset: read_u64(&data, offset_of(offset, 1, 0x10)?)? as usize,
+                    })
+                } else {
+                    let offset = offset_of(rows_offset, i, 0x0C)?;
+                    Ok(ParamFileRow {
+                        id: read_u32(&data, offset)? as u64,
+                        data_offset: read_u32(&data, offset_of(offset, 1, 0x04)?)? as usize,
+                        name_offset: read_u32(&data, offset_of(offset, 1, 0x08)?)? as usize,
+                    })
+                }
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+
+        // Rows are stored back to back between the row headers and the
+        // strings.
+        let row_size = match rows.as_slice() {
+            [] => 0,
+            [row] => strings_offset.min(data.len()).saturating_sub(row.data_offset),
+            [first, second, ..] => second.data_offset.saturating_sub(first.data_offset),
+        };
+
+        if let Some(row) = rows
+            .iter()
+            .find(|row| row.data_offset.checked_add(row_size).is_none_or(|end| end > data.len()))
+        {
+            return Err(format!("Row {} data out of bounds", row.id));
+        }
+
+        Ok(ParamFile {
+            data,
+            param_type,
+            unicode: format_2e & PARAM_UNICODE_NAMES != 0,
+            rows,
+            row_size,
+        })
+    }
+
+    /// The paramdef type, e.g. `EQUIP_PARAM_WEAPON_ST`.
+    pub fn param_type(&self) -> &str {
+        &self.param_type
+    }
+
+    pub fn len(&self) -> usize {
+        self.rows.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+
+    /// Size of each row in bytes.
+    pub fn row_size(&self) -> usize {
+        self.row_size
+    }
+
+    pub fn ids(&self) -> impl Iterator<Item = u64> + '_ {
+        self.rows.iter().map(|row| row.id)
+    }
+
+    /// The raw bytes of the row at index `idx`.
+    pub fn row_data(&self, idx: usize) -> Option<&[u8]> {
+        let row = self.rows.get(idx)?;
+        self.data.get(row.data_offset..row.data_offset.checked_add(self.row_size)?)
+    }
+
+    /// The name stored in the file for the row at index `idx`. Most rows
+    /// don't have one.
+    pub fn row_name(&self, idx: usize) -> Option<String> {
+        let row = self.rows.get(idx)?;
+
+        if row.name_offset == 0 {
+            return None;
+        }
+
+        read_str(&self.data, row.name_offset, self.unicode).ok().filter(|name| !name.is_empty())
+    }
+
+    /// Reads the row at index `idx` as a param struct. Returns `None` if the
+    /// struct is larger than the rows of this file.
+    pub fn get<T: ParamStruct>(&self, idx: usize) -> Option<(u64, T)> {
+        let row = self.rows.get(idx)?;
+        let data = self.row_data(idx)?;
+
+        if data.len() < mem::size_of::<T>() {
+            return None;
+        }
+
+        // Param structs are plain data for which every bit pattern is valid.
+        Some((row.id, unsafe { std::ptr::read_unaligned(data.as_ptr() as *const T) }))
+    }
+
+    /// Iterates the rows as param structs, with their ids.
+    pub fn iter<T: ParamStruct>(&self) -> impl Iterator<Item = (u64, T)> + '_ {
+        (0..self.rows.len()).filter_map(|idx| self.get(idx))
+    }
+
+    /// Visits the row at index `idx` as a row of the param `param`. Returns
+    /// `false` if the param is unknown or its rows don't fit this file.
+    pub fn visit_row_ref<T: ParamRefVisitor>(
+        &self,
+        param: &str,
+        idx: usize,
+        visitor: &mut T,
+    ) -> bool {
+        let (Some(lambda), Some(&size), Some(data)) =
+            (PARAM_REF_VTABLE.get(param), PARAM_SIZES.get(param), self.row_data(idx))
+        else {
+            return false;
+        };
+
+        if data.len() < size {
+            return false;
+        }
+
+        // Copy into u64 storage, which guarantees alignment for every field
+        // type.
+        let mut buf = vec![0u64; size.div_ceil(mem::size_of::<u64>())];
+        unsafe { std::ptr::copy_nonoverlapping(data.as_ptr(), buf.as_mut_ptr() as *mut u8, size) };
+        lambda(buf.as_ptr() as _, visitor);
+
+        true
+    }
+}
+
+/// The PARAM files of a regulation, by param name (e.g. `EquipParamWeapon`).
+pub struct Regulation(BTreeMap<String, ParamFile>);
+
+impl Regulation {
+    pub fn read(path: &Path) -> Result<Regulation, String> {
+        let data =
+            std::fs::read(path).map_err(|e| format!("Couldn't read {}: {e}", path.display()))?;
+        Regulation::parse(&data)
+    }
+
+    /// Parses a BND4 archive. Files that aren't valid PARAM files are logged
+    /// and skipped.
+    pub fn parse(data: &[u8]) -> Result<Regulation, String> {
+        if data.starts_with(b"DCX\0") {
+            return Err("The regulation is compressed, decompress it first".to_string());
+        }
+
+        if !data.starts_with(b"BND4") {
+            return Err("Not a BND4 archive (is the regulation still encrypted?)".to_string());
+        }
+
+        if read_u8(data, 0x09)? != 0 {
+            return Err("Big endian archives are not supported".to_string());
+        }
+
+        let bit_big_endian = read_u8(data, 0x0A)? == 0;
+        let file_count = read_u32(data, 0x0C)? as usize;
+        let file_header_size = read_u64(data, 0x20)? as usize;
+        let unicode = read_u8(data, 0x30)? != 0;
+
+        let raw_format = read_u8(data, 0x31)?;
+        let format = if bit_big_endian || (raw_format & 0x01 != 0 && raw_format & 0x80 == 0) {
+            raw_format
+        } else {
+            raw_format.reverse_bits()
+        };
+
+        let mut params = BTreeMap::new();
+
+        for i in 0..file_count {
+            // Each field is read from the header before moving past it,
+            // which bounds `offset` by the archive's length.
+            let mut offset = offset_of(0x48, i, file_header_size)?;
+
+            let size = read_u64(data, offset)? as usize;
+            offset += 8;
+
+            if format & BND4_COMPRESSION != 0 {
+                read_u64(data, offset)?;
+                offset += 8;
+            }
+
+            let data_offset = if format & BND4_LONG_OFFSETS != 0 {
+                offset += 8;
+                read_u64(data, offset - 8)? as usize
+            } else {
+                offset += 4;
+                read_u32(data, offset - 4)? as usize
+            };
+
+            if format & BND4_IDS != 0 {
+                offset += 4;
+            }
+
+            if format & (BND4_NAMES1 | BND4_NAMES2) == 0 {
+                return Err("Archive has no file names".to_string());
+            }
+
+            let path = read_str(data, read_u32(data, offset)? as usize, unicode)?;
+            let name = path.rsplit(['\\', '/']).next().unwrap_or(&path);
+            let name = name.strip_suffix(".param").unwrap_or(name).to_string();
+
+            let file = data_offset
+                .checked_add(size)
+                .and_then(|end| data.get(data_offset..end))
+                .ok_or_else(|| format!("{name}: data out of bounds"))
+                .and_then(|file| ParamFile::parse(file.to_vec()));
+
+            match file {
+                Ok(file) => {
+                    params.insert(name, file);
+                },
+                Err(e) => error!("{name}: {e}"),
+            }
+        }
+
+        Ok(Regulation(params))
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &String> {
+        self.0.keys()
+    }
+
+    pub fn param(&self, name: &str) -> Option<&ParamFile> {
+        self.0.get(name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::params::EquipParamWeapon;
+    use crate::FieldValue;
+
+    /// Builds a PARAM file with long data offsets.
+    fn build_param(param_type: &str, rows: &[(u32, &[u8], &str)]) -> Vec<u8> {
+        let row_size = rows.first().map(|(_, data, _)| data.len()).unwrap_or(0);
+        let data_start = PARAM_HEADER_SIZE + rows.len() * 0x18;
+        let strings_offset = data_start + rows.len() * row_size;
+
+        let mut buf = vec![0u8; PARAM_HEADER_SIZE];
+        buf[0x00..0x04].copy_from_slice(&(strings_offset as u32).to_le_bytes());
+        buf[0x0A..0x0C].copy_from_slice(&(rows.len() as u16).to_le_bytes());
+        buf[0x0C..0x0C + param_type.len()].copy_from_slice(param_type.as_bytes());
+        buf[0x2D] = PARAM_FLAG_01 | PARAM_INT_DATA_OFFSET | PARAM_LONG_DATA_OFFSET;
+        buf[0x30..0x34].copy_from_slice(&(data_start as u32).to_le_bytes());
+
+        let mut strings = Vec::new();
+        for (i, (id, _, name)) in rows.iter().enumerate() {
+            let name_offset = strings_offset + strings.len();
+            strings.extend_from_slice(name.as_bytes());
+            strings.push(0);
+
+            buf.extend_from_slice(&(*id as u64).to_le_bytes());
+            buf.extend_from_slice(&((data_start + i * row_size) as u64).to_le_bytes());
+            buf.extend_from_slice(&(name_offset as u64).to_le_bytes());
+        }
+
+        for (_, data, _) in rows {
+            buf.extend_from_slice(data);
+        }
+
+        buf.extend_from_slice(&strings);
+        buf
+    }
+
+    /// Builds an uncompressed BND4 archive with the same format as DS3's
+    /// regulation.
+    fn build_bnd4(files: &[(&str, Vec<u8>)]) -> Vec<u8> {
+        const FILE_HEADER_SIZE: usize = 0x24;
+
+        let headers_end = 0x40 + files.len() * FILE_HEADER_SIZE;
+        let names = files
+            .iter()
+            .map(|(name, _)| {
+                let mut name = name.encode_utf16().flat_map(u16::to_le_bytes).collect::<Vec<_>>();
+                name.extend_from_slice(&[0, 0]);
+                name
+            })
+            .collect::<Vec<_>>();
+        let data_start = headers_end + names.iter().map(Vec::len).sum::<usize>();
+
+        let mut buf = vec![0u8; 0x40];
+        buf[0x00..0x04].copy_from_slice(b"BND4");
+        buf[0x0A] = 1;
+        buf[0x0C..0x10].copy_from_slice(&(files.len() as u32).to_le_bytes());
+        buf[0x10..0x18].copy_from_slice(&0x40u64.to_le_bytes());
+        buf[0x20..0x28].copy_from_slice(&(FILE_HEADER_SIZE as u64).to_le_bytes());
+        buf[0x28..0x30].copy_from_slice(&(data_start as u64).to_le_bytes());
+        buf[0x30] = 1;
+        // IDs | Names1 | Names2 | Compression, bit-reversed as in the game files.
+        buf[0x31] = 0x74;
+
+        let mut name_offset = headers_end;
+        let mut data_offset = data_start;
+        for ((_, data), name) in files.iter().zip(&names) {
+            buf.extend_from_slice(&[0x40, 0, 0, 0]);
+            buf.extend_from_slice(&(-1i32).to_le_bytes());
+            buf.extend_from_slice(&(data.len() as u64).to_le_bytes());
+            buf.extend_from_slice(&(data.len() as u64).to_le_bytes());
+            buf.extend_from_slice(&(data_offset as u32).to_le_bytes());
+            buf.extend_from_slice(&0u32.to_le_bytes());
+            buf.extend_from_slice(&(name_offset as u32).to_le_bytes());
+            name_offset += name.len();
+            data_offset += data.len();
+        }
+
+        for name in &names {
+            buf.extend_from_slice(name);
+        }
+
+        for (_, data) in files {
+            buf.extend_from_slice(data);
+        }
+
+        buf
+    }
+
+    fn weapon_row(weight: f32, sort_id: i32) -> Vec<u8> {
+        let mut row: EquipParamWeapon = unsafe { mem::zeroed() };
+        row.weight = weight;
+        row.sort_id = sort_id;
+
+        let ptr = &row as *const EquipParamWeapon as *const u8;
+        unsafe { std::slice::from_raw_parts(ptr, mem::size_of::<EquipParamWeapon>()) }.to_vec()
+    }
+
+    #[test]
+    fn test_param_file() {
+        let (dagger, bandit_knife) = (weapon_row(1.5, 10), weapon_row(1.0, 20));
+        let data = build_param("EQUIP_PARAM_WEAPON_ST", &[
+            (1000000, &dagger, "Dagger"),
+            (1001000, &bandit_knife, ""),
+        ]);
+
+        let file = ParamFile::parse(data).unwrap();
+        assert_eq!(file.param_type(), "EQUIP_PARAM_WEAPON_ST");
+        assert_eq!(file.len(), 2);
+        assert_eq!(file.row_size(), mem::size_of::<EquipParamWeapon>());
+        assert_eq!(file.ids().collect::<Vec<_>>(), vec![1000000, 1001000]);
+        assert_eq!(file.row_name(0).as_deref(), Some("Dagger"));
+        assert_eq!(file.row_name(1), None);
+
+        let (id, row) = file.get::<EquipParamWeapon>(1).unwrap();
+        assert_eq!(id, 1001000);
+        assert_eq!(row.weight, 1.0);
+        assert_eq!(row.sort_id, 20);
+        assert_eq!(file.iter::<EquipParamWeapon>().count(), 2);
+
+        struct SortId(Option<FieldValue>);
+        impl ParamRefVisitor for SortId {
+            fn visit_field(&mut self, name: &'static str, value: FieldValue) {
+                if name == "sort_id" {
+                    self.0 = Some(value);
+                }
+            }
+        }
+
+        let mut visitor = SortId(None);
+        assert!(file.visit_row_ref("EquipParamWeapon", 0, &mut visitor));
+        assert_eq!(visitor.0, Some(FieldValue::I32(10)));
+        assert!(!file.visit_row_ref("NotAParam", 0, &mut visitor));
+    }
+
+    #[test]
+    fn test_param_file_truncated() {
+        let row = weapon_row(1.5, 10);
+        let mut data = build_param("EQUIP_PARAM_WEAPON_ST", &[(1, &row, ""), (2, &row, "")]);
+        data.truncate(PARAM_HEADER_SIZE + 0x18 * 2 + row.len());
+
+        assert!(ParamFile::parse(data).is_err());
+        assert!(ParamFile::parse(vec![0; 0x10]).is_err());
+
+        // A data offset close to usize::MAX must not overflow.
+        let mut data = build_param("EQUIP_PARAM_WEAPON_ST", &[(1, &row, "")]);
+        let offset = PARAM_HEADER_SIZE + 0x08;
+        data[offset..offset + 8].copy_from_slice(&u64::MAX.to_le_bytes());
+        assert!(ParamFile::parse(data).is_err());
+    }
+
+    #[test]
+    fn test_regulation() {
+        let row = weapon_row(1.5, 10);
+        let weapons = build_param("EQUIP_PARAM_WEAPON_ST", &[(1000000, &row, "")]);
+        let data = build_bnd4(&[
+            ("N:\\FDP\\data\\Param\\param\\GameParam\\EquipParamWeapon.param", weapons),
+            ("N:\\FDP\\data\\Param\\param\\GameParam\\Broken.param", vec![0; 4]),
+        ]);
+
+        let regulation = Regulation::parse(&data).unwrap();
+        assert_eq!(regulation.keys().collect::<Vec<_>>(), vec!["EquipParamWeapon"]);
+
+        let weapons = regulation.param("EquipParamWeapon").unwrap();
+        assert_eq!(weapons.get::<EquipParamWeapon>(0).unwrap().1.sort_id, 10);
+
+        // Huge header sizes and file offsets must not overflow.
+        let mut huge_header = data.clone();
+        huge_header[0x20..0x28].copy_from_slice(&u64::MAX.to_le_bytes());
+        assert!(Regulation::parse(&huge_header).is_err());
+
+        let mut huge_size = data.clone();
+        huge_size[0x48..0x50].copy_from_slice(&u64::MAX.to_le_bytes());
+        // The file is skipped, as it doesn't fit in the archive.
+        assert_eq!(Regulation::parse(&huge_size).unwrap().keys().count(), 0);
+
+        assert!(Regulation::parse(b"DCX\0").is_err());
+        assert!(Regulation::parse(b"garbage").is_err());
+    }
+}
@@ -4,6 +4,7 @@ use std::mem::size_of;
 use log::debug;
 use windows::Win32::System::LibraryLoader::GetModuleHandleA;
 
+use crate::inventory::Inventory;
 use crate::memedit::*;
 use crate::prelude::base_addresses::BaseAddresses;
 use crate::prelude::{Version, VERSION};
@@ -27,6 +28,7 @@ pub struct CharacterStats {
     pub vitality: i32,
     pub level: i32,
     pub souls: i32,
+    pub soul_memory: i32,
 }
 
 impl Display for CharacterStats {
@@ -35,6 +37,48 @@ impl Display for CharacterStats {
     }
 }
 
+/// Covenants, by their value in `PlayerGameData`.
+pub const COVENANTS: [&str; 10] = [
+    "None",
+    "Way of Blue",
+    "Blue Sentinels",
+    "Blade of the Darkmoon",
+    "Warrior of Sunlight",
+    "Rosaria's Fingers",
+    "Watchdogs of Farron",
+    "Aldrich Faithful",
+    "Mound-makers",
+    "Spears of the Church",
+];
+
+/// Id of a map block, `mAA_BB_CC_DD`, one byte per component from the most
+/// significant.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MapId(pub u32);
+
+impl MapId {
+    pub fn area(&self) -> u8 {
+        (self.0 >> 24) as u8
+    }
+
+    pub fn block(&self) -> u8 {
+        (self.0 >> 16) as u8
+    }
+
+    /// Whether both ids share the same area and block, i.e. world
+    /// coordinates are comparable across them.
+    pub fn same_block(&self, other: &MapId) -> bool {
+        self.area() == other.area() && self.block() == other.block()
+    }
+}
+
+impl Display for MapId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
+        let [a, b, c, d] = self.0.to_be_bytes();
+        write!(f, "m{a:02}_{b:02}_{c:02}_{d:02}")
+    }
+}
+
 // Pointer chains
 //
 
@@ -64,8 +108,13 @@ pub struct PointerChains {
     pub gravity: Bitflag<u8>,
     pub speed: PointerChain<f32>,
     pub position: (PointerChain<f32>, PointerChain<[f32; 3]>),
+    pub map_id: PointerChain<u32>,
     pub character_stats: PointerChain<CharacterStats>,
     pub souls: PointerChain<u32>,
+    pub ng_cycle: PointerChain<u32>,
+    pub covenant: PointerChain<u8>,
+    pub hollowing: PointerChain<u8>,
+    pub inventory: Inventory,
     pub quitout: PointerChain<u8>,
     pub cursor_show: Bitflag<u8>,
     pub igt: PointerChain<u32>,
@@ -251,9 +300,16 @@ impl From<BaseAddresses> for PointerChains {
                 pointer_chain!(world_chr_man, 0x40, 0x28, 0x74),
                 pointer_chain!(world_chr_man, 0x40, 0x28, 0x80),
             ),
+            // The map id offset isn't verified on every game version, so
+            // readers must cope with an unknown or wrong map.
+            map_id: pointer_chain!(world_chr_man, 0x80, 0x1ABC),
             character_stats: pointer_chain!(base_a, 0x10, 0x44),
             // souls was previously pointer_chain!(sprj_debug_event as _, 0x3d0, 0x74),
             souls: pointer_chain!(base_a, 0x10, 0x44 + 12 * size_of::<i32>()),
+            ng_cycle: pointer_chain!(base_a, 0x78),
+            covenant: pointer_chain!(base_a, 0x10, 0xF7),
+            hollowing: pointer_chain!(base_a, 0x10, 0x104),
+            inventory: Inventory::new(base_a),
             map_item_man: map_item_man as _,
             spawn_item_func_ptr: spawn_item_func_ptr as _,
             travel_ptr: menu_travel,
@@ -284,3 +340,35 @@ impl PointerChains {
         base_addresses.into()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_map_id() {
+        // High Wall of Lothric, and another id in its block.
+        let high_wall = MapId(0x1E_00_00_00);
+        let same_block = MapId(0x1E_00_01_00);
+        // Lothric Castle, another block of the same area.
+        let castle = MapId(0x1E_01_00_00);
+        // Undead Settlement, another area.
+        let settlement = MapId(0x1F_00_00_00);
+
+        assert_eq!((high_wall.area(), high_wall.block()), (30, 0));
+        assert_eq!((castle.area(), castle.block()), (30, 1));
+
+        assert!(high_wall.same_block(&high_wall));
+        assert!(high_wall.same_block(&same_block));
+        assert!(!high_wall.same_block(&castle));
+        assert!(!high_wall.same_block(&settlement));
+        assert!(!MapId(0x1F_01_00_00).same_block(&castle));
+    }
+
+    #[test]
+    fn test_map_id_display() {
+        assert_eq!(MapId(0x1E_01_00_00).to_string(), "m30_01_00_00");
+        assert_eq!(MapId(0x28_00_02_0A).to_string(), "m40_00_02_10");
+        assert_eq!(MapId(0).to_string(), "m00_00_00_00");
+    }
+}
@@ -0,0 +1,113 @@
+//! Pattern scans of the game's code, run at startup.
+//!
+//! Most addresses come from the generated base addresses. This covers the
+//! ones that haven't been added there yet: a missing match makes the feature
+//! relying on it unavailable instead of failing the whole tool.
+
+use std::slice;
+
+use once_cell::sync::Lazy;
+use windows::Win32::System::LibraryLoader::GetModuleHandleA;
+
+/// `FieldArea`, which holds the world block info and the cameras.
+pub(crate) static FIELD_AREA: Lazy<Option<usize>> = Lazy::new(|| {
+    aob_indirect(
+        &["4C 8B 3D ?? ?? ?? ?? 8B 45 87 83 F8 FF 74 69 48 8D 4D 8F 48 89 4D 9F 89 45 8F"],
+        3,
+        7,
+    )
+});
+
+/// Parses a pattern such as `"48 8B 05 ?? ?? ?? ??"`. Wildcards are `None`.
+fn parse_pattern(pattern: &str) -> Option<Vec<Option<u8>>> {
+    pattern
+        .split_whitespace()
+        .map(|byte| match byte {
+            "??" => Some(None),
+            byte => u8::from_str_radix(byte, 16).ok().map(Some),
+        })
+        .collect()
+}
+
+/// Offset of the first match of `pattern` in `haystack`.
+fn find_pattern(haystack: &[u8], pattern: &[Option<u8>]) -> Option<usize> {
+    if pattern.is_empty() {
+        return None;
+    }
+
+    haystack.windows(pattern.len()).position(|window| {
+        window.iter().zip(pattern).all(|(byte, expected)| expected.is_none_or(|e| e == *byte))
+    })
+}
+
+/// The main module's `.text` section.
+///
+/// # Safety
+///
+/// Reads the PE headers of the main module, which must be mapped.
+unsafe fn code_section() -> Option<&'static [u8]> {
+    let base = GetModuleHandleA(None).ok()?.0 as usize;
+
+    let nt_headers = base + *((base + 0x3c) as *const u32) as usize;
+    let section_count = *((nt_headers + 0x6) as *const u16) as usize;
+    let optional_header_size = *((nt_headers + 0x14) as *const u16) as usize;
+    let sections = nt_headers + 0x18 + optional_header_size;
+
+    (0..section_count).map(|i| sections + i * 0x28).find_map(|section| {
+        let name = slice::from_raw_parts(section as *const u8, 8);
+        if !name.starts_with(b".text\0") {
+            return None;
+        }
+
+        let size = *((section + 0x8) as *const u32) as usize;
+        let address = *((section + 0xc) as *const u32) as usize;
+        Some(slice::from_raw_parts((base + address) as *const u8, size))
+    })
+}
+
+/// Address of the first match of any of `patterns` in the game's code. This
+/// is what the base addresses codegen calls a direct scan.
+pub fn aob_direct(patterns: &[&str]) -> Option<usize> {
+    let code = unsafe { code_section() }?;
+
+    patterns
+        .iter()
+        .find_map(|pattern| find_pattern(code, &parse_pattern(pattern)?))
+        .map(|found| code.as_ptr() as usize + found)
+}
+
+/// Finds the first of `patterns` that matches in the game's code, and
+/// resolves the rip-relative address stored `offset` bytes into the match,
+/// in an instruction `next_instr` bytes long. This is what the base
+/// addresses codegen calls an indirect scan.
+pub fn aob_indirect(patterns: &[&str], offset: usize, next_instr: usize) -> Option<usize> {
+    let code = unsafe { code_section() }?;
+
+    patterns.iter().find_map(|pattern| {
+        let found = find_pattern(code, &parse_pattern(pattern)?)?;
+        let displacement = code.get(found + offset..found + offset + 4)?;
+        let displacement = i32::from_le_bytes(displacement.try_into().ok()?);
+
+        let next_instr = code.as_ptr() as usize + found + next_instr;
+        Some(next_instr.wrapping_add_signed(displacement as isize))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_pattern() {
+        let code = [0x90, 0x48, 0x8B, 0x05, 0x10, 0x20, 0x30, 0x40, 0xC3];
+
+        let pattern = parse_pattern("48 8B 05 ?? ?? ?? ?? C3").unwrap();
+        assert_eq!(find_pattern(&code, &pattern), Some(1));
+
+        let pattern = parse_pattern("48 8B 0D ?? ?? ?? ??").unwrap();
+        assert_eq!(find_pattern(&code, &pattern), None);
+
+        assert_eq!(find_pattern(&code, &[]), None);
+        assert!(parse_pattern("48 8G").is_none());
+    }
+}
@@ -0,0 +1,121 @@
+//! Warping to any bonfire, lit or not, without the travel menu.
+//!
+//! Bonfires are the rows of `BonfireWarpParam`. Warping writes the bonfire's
+//! warp event in `GameMan`, where the travel menu leaves its pick, and then
+//! requests the warp, as the travel menu does once a bonfire is picked.
+
+use once_cell::sync::Lazy;
+
+use crate::memedit::PointerChain;
+use crate::params::{BonfireWarpParam, Params, PARAM_NAMES};
+use crate::{pointer_chain, scan};
+
+/// `GameMan`, found by a pattern scan as it's not a generated base address.
+static GAME_MAN: Lazy<Option<usize>> = Lazy::new(|| {
+    scan::aob_indirect(&["48 8B 05 ?? ?? ?? ?? 80 B8 ?? ?? ?? ?? 00 0F 84 ?? ?? ?? ?? C6 83"], 3, 7)
+});
+
+/// Offset of the warp destination in `GameMan`.
+const OFFS_WARP_DESTINATION: usize = 0xACC;
+/// Offset of the warp request flag in `GameMan`.
+const OFFS_WARP_REQUEST: usize = 0xB64;
+
+#[derive(Debug, Clone)]
+pub struct Bonfire {
+    /// Row id in `BonfireWarpParam`.
+    pub id: u64,
+    pub warp_event_id: i32,
+    pub name: String,
+}
+
+/// Every bonfire in `BonfireWarpParam`.
+pub fn bonfires(params: &Params) -> Vec<Bonfire> {
+    let Some(rows) = (unsafe { params.iter_param::<BonfireWarpParam>("BonfireWarpParam") }) else {
+        return Vec::new();
+    };
+
+    rows.filter_map(|row| {
+        let warp_event_id = row.param?.warp_event_id;
+        (warp_event_id > 0).then(|| Bonfire {
+            id: row.id,
+            warp_event_id,
+            name: bonfire_name(row.id),
+        })
+    })
+    .collect()
+}
+
+/// Warp event of the bonfire with row id `id`.
+pub fn bonfire_warp_event(params: &Params, id: u64) -> Option<i32> {
+    let warp_event_id = unsafe { params.get_bonfire_warp_param_by_id(id) }?.param?.warp_event_id;
+    (warp_event_id > 0).then_some(warp_event_id)
+}
+
+/// Name of the bonfire with row id `id`, after the param row.
+pub fn bonfire_name(id: u64) -> String {
+    PARAM_NAMES
+        .get("BonfireWarpParam")
+        .and_then(|names| names.get(&(id as usize)))
+        .map(|name| strip_row_name(name))
+        .unwrap_or_else(|| format!("Bonfire {id}"))
+}
+
+/// Drops the Japanese name and the `[... bonfire]` tag from a row name.
+fn strip_row_name(row_name: &str) -> String {
+    let name = row_name.split(" -- ").next().unwrap_or(row_name).trim();
+    let name = match name.strip_prefix(['[', '【']) {
+        Some(tagged) => tagged.split_once([']', '】']).map(|(_, name)| name.trim()).unwrap_or(name),
+        None => name,
+    };
+
+    name.to_string()
+}
+
+#[derive(Debug, Clone)]
+pub struct BonfireWarp {
+    destination: PointerChain<i32>,
+    request: PointerChain<u8>,
+}
+
+impl BonfireWarp {
+    /// Returns `None` if `GameMan` couldn't be found in this game version.
+    pub fn new() -> Option<Self> {
+        let game_man = (*GAME_MAN)?;
+
+        Some(BonfireWarp {
+            destination: pointer_chain!(game_man, OFFS_WARP_DESTINATION),
+            request: pointer_chain!(game_man, OFFS_WARP_REQUEST),
+        })
+    }
+
+    /// Warps to the bonfire's warp event. Returns `None` when not in game.
+    pub fn warp(&self, warp_event_id: i32) -> Option<()> {
+        self.destination.write(warp_event_id)?;
+        self.request.write(1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strip_row_name() {
+        assert_eq!(
+            strip_row_name(
+                "[Boss Bonfire] Prince of Darkness (innermost) -- 【ボス篝火】闇の双皇子（最奥）"
+            ),
+            "Prince of Darkness (innermost)"
+        );
+        assert_eq!(
+            strip_row_name(
+                "【 Normal Bonfire 】 shortcut route -- 【通常篝火】ショートカットルート途中"
+            ),
+            "shortcut route"
+        );
+        assert_eq!(
+            strip_row_name("The big wall of Le dress -- ルドレスの大壁"),
+            "The big wall of Le dress"
+        );
+    }
+}
@@ -3,7 +3,51 @@ use proc_macro::TokenStream;
 use quote::{format_ident, quote};
 use syn::*;
 
-#[proc_macro_derive(ParamStruct, attributes(bitflag))]
+/// Parses `#[field_meta(display_name = "..", description = "..", enum_name =
+/// "..", min = "..", max = "..")]` into a `FieldMeta` expression.
+fn parse_field_meta(meta_list: &MetaList) -> proc_macro2::TokenStream {
+    let mut display_name = quote! { None };
+    let mut description = quote! { None };
+    let mut enum_name = quote! { None };
+    let mut min = quote! { None };
+    let mut max = quote! { None };
+
+    for nested in &meta_list.nested {
+        let (path, value) = match nested {
+            NestedMeta::Meta(Meta::NameValue(MetaNameValue {
+                path, lit: Lit::Str(value), ..
+            })) => (path.get_ident().unwrap().to_string(), value.value()),
+            other => panic!("Wrong field_meta parameter: {:#?}", other),
+        };
+
+        match path.as_str() {
+            "display_name" => display_name = quote! { Some(#value) },
+            "description" => description = quote! { Some(#value) },
+            "enum_name" => enum_name = quote! { Some(#value) },
+            "min" | "max" => {
+                let value = value.parse::<f64>().unwrap();
+                if path == "min" {
+                    min = quote! { Some(#value) };
+                } else {
+                    max = quote! { Some(#value) };
+                }
+            },
+            other => panic!("Unknown field_meta parameter: {}", other),
+        }
+    }
+
+    quote! {
+        FieldMeta {
+            display_name: #display_name,
+            description: #description,
+            enum_name: #enum_name,
+            min: #min,
+            max: #max,
+        }
+    }
+}
+
+#[proc_macro_derive(ParamStruct, attributes(bitflag, field_meta))]
 pub fn macro_param(t: TokenStream) -> TokenStream {
     let input = parse_macro_input!(t as DeriveInput);
     let name = input.ident;
@@ -15,49 +59,44 @@ pub fn macro_param(t: TokenStream) -> TokenStream {
     let fields_with_bitfields = fields_punct
         .iter()
         .map(|field| {
-            (
-                field,
-                field
-                    .attrs
-                    .iter()
-                    .map(|attr| {
-                        let meta_list = match attr.parse_meta() {
-                            Ok(Meta::List(meta_list)) if meta_list.path.is_ident("bitflag") => {
-                                meta_list
-                            },
-                            other => unimplemented!("Unimplemented attribute {:#?}", other),
-                        };
-
-                        match (&meta_list.nested[0], &meta_list.nested[1]) {
-                            (
-                                NestedMeta::Meta(Meta::Path(path)),
-                                NestedMeta::Lit(Lit::Int(fieldno)),
-                            ) => {
-                                let bitfield_name = path.get_ident().unwrap().to_owned();
-                                let set_ident = format_ident!(
-                                    "set_{}",
-                                    AsSnakeCase(bitfield_name.to_string()).to_string()
-                                );
-                                let get_ident = format_ident!(
-                                    "{}",
-                                    AsSnakeCase(bitfield_name.to_string()).to_string()
-                                );
-                                (
-                                    bitfield_name,
-                                    fieldno.base10_parse::<u8>().unwrap(),
-                                    set_ident,
-                                    get_ident,
-                                )
-                            },
-                            other => panic!("Wrong attribute parameters: {:#?}", other),
-                        }
-                    })
-                    .collect::<Vec<_>>(),
-            )
+            let mut bitfield_spec = Vec::new();
+            let mut field_meta = None;
+
+            for attr in &field.attrs {
+                let meta_list = match attr.parse_meta() {
+                    Ok(Meta::List(meta_list)) if meta_list.path.is_ident("field_meta") => {
+                        field_meta = Some(parse_field_meta(&meta_list));
+                        continue;
+                    },
+                    Ok(Meta::List(meta_list)) if meta_list.path.is_ident("bitflag") => meta_list,
+                    other => unimplemented!("Unimplemented attribute {:#?}", other),
+                };
+
+                match (&meta_list.nested[0], &meta_list.nested[1]) {
+                    (NestedMeta::Meta(Meta::Path(path)), NestedMeta::Lit(Lit::Int(fieldno))) => {
+                        let bitfield_name = path.get_ident().unwrap().to_owned();
+                        let set_ident = format_ident!(
+                            "set_{}",
+                            AsSnakeCase(bitfield_name.to_string()).to_string()
+                        );
+                        let get_ident =
+                            format_ident!("{}", AsSnakeCase(bitfield_name.to_string()).to_string());
+                        bitfield_spec.push((
+                            bitfield_name,
+                            fieldno.base10_parse::<u8>().unwrap(),
+                            set_ident,
+                            get_ident,
+                        ));
+                    },
+                    other => panic!("Wrong attribute parameters: {:#?}", other),
+                }
+            }
+
+            (field, bitfield_spec, field_meta)
         })
         .collect::<Vec<_>>();
 
-    let bitfield_methods = fields_with_bitfields.iter().flat_map(|(field, bitfield_spec)| {
+    let bitfield_methods = fields_with_bitfields.iter().flat_map(|(field, bitfield_spec, _)| {
         bitfield_spec.iter().map(|(_, field_idx, set_ident, get_ident)| {
             let ident = format_ident!("{}", field.ident.as_ref().unwrap());
             quote! {
@@ -78,33 +117,66 @@ pub fn macro_param(t: TokenStream) -> TokenStream {
         })
     });
 
-    let field_visit = fields_with_bitfields
+    let (field_visit, field_visit_ref): (Vec<_>, Vec<_>) = fields_with_bitfields
         .iter()
-        .filter_map(|(field, bitfield_spec)| match field {
+        .filter_map(|(field, bitfield_spec, field_meta)| match field {
             &Field { ident: Some(ident), ty: Type::Path(TypePath { path, .. }), .. } => {
                 let ty_ident = path.segments[0].ident.to_string();
                 match ty_ident.as_str() {
-                    "u8" if !bitfield_spec.is_empty() => {
-                        let bitfield_visit = bitfield_spec.iter().map(
-                            |(bitfield_name, _, set_bitfield, get_bitfield)| {
+                    "u8" | "u16" | "u32" if !bitfield_spec.is_empty() => {
+                        // Bits are visited by the snake case name of their
+                        // getter instead of as a whole integer.
+                        let bitfield_visit =
+                            bitfield_spec.iter().map(|(_, bit, set_bitfield, get_bitfield)| {
                                 quote! {
                                     let mut b = self.#get_bitfield();
-                                    t.visit_bool(stringify!(#bitfield_name), &mut b);
+                                    t.visit_bit(
+                                        stringify!(#get_bitfield),
+                                        stringify!(#ident),
+                                        #bit,
+                                        &mut b,
+                                    );
                                     self.#set_bitfield(b);
                                 }
-                            },
-                        );
+                            });
+                        let bitfield_visit_ref =
+                            bitfield_spec.iter().map(|(_, _, _, get_bitfield)| {
+                                quote! {
+                                    t.visit_field(
+                                        stringify!(#get_bitfield),
+                                        FieldValue::Bool(self.#get_bitfield()),
+                                    );
+                                }
+                            });
 
-                        Some(quote! {
-                            #(#bitfield_visit)*
-                        })
+                        Some((
+                            quote! {
+                                #(#bitfield_visit)*
+                            },
+                            quote! {
+                                #(#bitfield_visit_ref)*
+                            },
+                        ))
                     },
                     "u8" | "u16" | "u32" | "i8" | "i16" | "i32" | "f32" => {
                         let ident = format_ident!("{}", ident);
                         let visit_ty = format_ident!("visit_{}", ty_ident);
-                        Some(quote! {
-                            t.#visit_ty(stringify!(#ident), &mut self.#ident);
-                        })
+                        let value_variant = format_ident!("{}", ty_ident.to_uppercase());
+                        let visit_meta = field_meta.as_ref().map(|field_meta| {
+                            quote! {
+                                t.visit_meta(stringify!(#ident), &#field_meta);
+                            }
+                        });
+                        Some((
+                            quote! {
+                                #visit_meta
+                                t.#visit_ty(stringify!(#ident), &mut self.#ident);
+                            },
+                            quote! {
+                                #visit_meta
+                                t.visit_field(stringify!(#ident), FieldValue::#value_variant(self.#ident));
+                            },
+                        ))
                     },
                     other => panic!("Unrecognized type {:#?}", other),
                 }
@@ -126,15 +198,21 @@ pub fn macro_param(t: TokenStream) -> TokenStream {
                 panic!("Unrecognized field {:#?}", field);
             },
         })
-        .collect::<Vec<_>>();
+        .unzip();
 
     let visit = quote! {
         fn visit<T: ParamVisitor + ?Sized>(&mut self, t: &mut T) {
             #(#field_visit)*
         }
+
+        fn visit_ref<T: ParamRefVisitor + ?Sized>(&self, t: &mut T) {
+            #(#field_visit_ref)*
+        }
     };
 
     let get_name_snake_case = format_ident!("get_{}", AsSnakeCase(name.to_string()).to_string());
+    let get_by_id_name_snake_case =
+        format_ident!("get_{}_by_id", AsSnakeCase(name.to_string()).to_string());
     quote! {
         impl #name {
             #(#bitfield_methods)*
@@ -148,6 +226,10 @@ pub fn macro_param(t: TokenStream) -> TokenStream {
             pub unsafe fn #get_name_snake_case(&self) -> Option<impl Iterator<Item = Param<#name>>> {
                 self.iter_param::<#name>(stringify!(#name))
             }
+
+            pub unsafe fn #get_by_id_name_snake_case(&self, id: u64) -> Option<Param<#name>> {
+                self.get_by_id::<#name>(stringify!(#name), id)
+            }
         }
     }
     .into()
@@ -1,3 +1,6 @@
 fn main() {
-    println!("cargo:rustc-cdylib-link-arg=/DEF:lib/no-logo/exports.def");
+    // The patch language and linter also build on other platforms.
+    if std::env::var("CARGO_CFG_TARGET_OS").as_deref() == Ok("windows") {
+        println!("cargo:rustc-cdylib-link-arg=/DEF:lib/no-logo/exports.def");
+    }
 }
@@ -0,0 +1,199 @@
+use std::ffi::{c_void, OsString};
+use std::os::windows::prelude::OsStringExt;
+use std::path::{Path, PathBuf};
+use std::sync::Once;
+use std::time::Duration;
+
+use libds3::prelude::*;
+use once_cell::sync::{Lazy, OnceCell};
+use windows::core::{GUID, HRESULT, PCSTR};
+use windows::Win32::Foundation::{BOOL, HINSTANCE, HMODULE, MAX_PATH};
+use windows::Win32::System::Console::AllocConsole;
+use windows::Win32::System::LibraryLoader::{GetModuleFileNameW, GetProcAddress, LoadLibraryA};
+use windows::Win32::System::SystemServices::{DLL_PROCESS_ATTACH, DLL_PROCESS_DETACH};
+
+use crate::patch_set::{self, PatchSet, PatchWatcher};
+use crate::vanilla::Vanilla;
+
+type FDirectInput8Create = unsafe extern "stdcall" fn(
+    hinst: HINSTANCE,
+    dwversion: u32,
+    riidltf: *const GUID,
+    ppvout: *mut *mut c_void,
+    punkouter: HINSTANCE,
+) -> HRESULT;
+
+struct State {
+    directinput8create: FDirectInput8Create,
+}
+
+unsafe impl Send for State {}
+unsafe impl Sync for State {}
+
+static STATE: Lazy<State> = Lazy::new(|| unsafe {
+    // TODO use GetSystemDirectory
+    let dinput8 = LoadLibraryA(PCSTR(b"C:\\Windows\\System32\\dinput8.dll\0".as_ptr())).unwrap();
+    let directinput8create =
+        std::mem::transmute(GetProcAddress(dinput8, PCSTR(b"DirectInput8Create\0".as_ptr())));
+
+    State { directinput8create }
+});
+
+static DLL_MODULE: OnceCell<isize> = OnceCell::new();
+
+/// The game may call `DirectInput8Create` more than once, but the patches
+/// must only be applied once: a second watcher would reapply relative edits
+/// on top of the patched values.
+static PATCH: Once = Once::new();
+
+fn initialize(dll_module: HINSTANCE) {
+    DLL_MODULE.set(dll_module.0).ok();
+    Lazy::force(&STATE);
+    unsafe { AllocConsole().ok() };
+}
+
+/// Returns the directory containing this DLL, which is where patch files are
+/// looked up.
+fn get_dll_dir() -> Option<PathBuf> {
+    let hmodule = HMODULE(*DLL_MODULE.get()?);
+    let mut sz_filename = [0u16; MAX_PATH as usize];
+    let len = unsafe { GetModuleFileNameW(hmodule, &mut sz_filename) } as usize;
+
+    if len == 0 {
+        return None;
+    }
+
+    let mut path = PathBuf::from(OsString::from_wide(&sz_filename[..len]));
+    path.pop();
+    Some(path)
+}
+
+fn no_logo() {
+    let pointer_chains = PointerChains::new();
+    pointer_chains
+        .no_logo
+        .write([
+            0x48, 0x31, 0xC0, 0x48, 0x89, 0x02, 0x49, 0x89, 0x04, 0x24, 0x90, 0x90, 0x90, 0x90,
+            0x90, 0x90, 0x90, 0x90, 0x90, 0x90,
+        ])
+        .unwrap();
+}
+
+/// Loads the patch files in `dir`. Returns the loaded files and the number of
+/// errors, which are logged.
+fn load_patches(dir: &Path) -> (PatchSet, usize) {
+    let paths = match patch_set::discover(dir) {
+        Ok(paths) => paths,
+        Err(e) => {
+            eprintln!("Error: {e}");
+            return (PatchSet::default(), 1);
+        },
+    };
+
+    if paths.is_empty() {
+        println!("No patch files found in {}", dir.display());
+    }
+
+    let (patch_set, errors) = PatchSet::load(&paths);
+    for e in &errors {
+        eprintln!("Error: {e}");
+    }
+
+    (patch_set, errors.len())
+}
+
+/// Reverts every field patched so far to its vanilla value, then applies the
+/// patch set.
+fn apply_patches(patch_set: &PatchSet, error_count: usize, vanilla: &mut Vanilla) {
+    let params = PARAMS.write();
+    vanilla.revert(&params);
+
+    let summary = patch_set.apply(&params, vanilla);
+
+    print!("{summary}");
+    if error_count > 0 {
+        println!("  {error_count} error(s), see above");
+    }
+}
+
+unsafe fn patch() {
+    no_logo();
+    std::thread::spawn(|| {
+        let dir = get_dll_dir().unwrap_or_else(|| PathBuf::from("."));
+        let mut watcher = PatchWatcher::new(&dir);
+        let mut vanilla = Vanilla::default();
+
+        let (mut patch_set, error_count) = load_patches(&dir);
+
+        {
+            let mut params = PARAMS.write();
+            drop(wait_option(|| {
+                if let Err(e) = params.refresh() {
+                    eprintln!("Error: {:?}", e);
+                }
+                params.get_equip_param_weapon()
+            }));
+        }
+
+        apply_patches(&patch_set, error_count, &mut vanilla);
+
+        loop {
+            std::thread::sleep(Duration::from_secs(1));
+
+            let refreshed = PARAMS.write().ensure_fresh().unwrap_or_else(|e| {
+                eprintln!("Error: {e}");
+                false
+            });
+
+            if refreshed {
+                // The reloaded rows hold vanilla values again, and the recorded
+                // ones point to the previous rows.
+                println!("Params were reloaded, reapplying patches");
+                vanilla = Vanilla::default();
+                apply_patches(&patch_set, 0, &mut vanilla);
+            }
+
+            if !watcher.changed() {
+                continue;
+            }
+
+            println!("Patch files changed, reloading");
+            let (new_patch_set, error_count) = load_patches(&dir);
+
+            // Keep the current patches until every file is valid, instead of
+            // reverting the fields of a file that is being edited.
+            if error_count > 0 {
+                println!("Not reloading: {error_count} error(s), see above");
+                continue;
+            }
+
+            patch_set = new_patch_set;
+            apply_patches(&patch_set, error_count, &mut vanilla);
+        }
+    });
+}
+
+#[no_mangle]
+unsafe extern "stdcall" fn DirectInput8Create(
+    hinst: HINSTANCE,
+    dwversion: u32,
+    riidltf: *const GUID,
+    ppvout: *mut *mut c_void,
+    punkouter: HINSTANCE,
+) -> HRESULT {
+    PATCH.call_once(|| patch());
+
+    (STATE.directinput8create)(hinst, dwversion, riidltf, ppvout, punkouter)
+}
+
+#[no_mangle]
+#[allow(non_snake_case, unused_variables)]
+extern "system" fn DllMain(dll_module: HINSTANCE, call_reason: u32, reserved: *mut c_void) -> BOOL {
+    match call_reason {
+        DLL_PROCESS_ATTACH => initialize(dll_module),
+        DLL_PROCESS_DETACH => (),
+        _ => (),
+    }
+
+    BOOL::from(true)
+}
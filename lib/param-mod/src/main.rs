@@ -0,0 +1,70 @@
+use std::path::PathBuf;
+
+use dinput8parammod::patch_set::{self, PatchSet};
+use libds3::prelude::*;
+
+const USAGE: &str = "Usage: param-mod-lint [--regulation <file>] [patch file]...
+
+Without patch files, checks the patch files found in the current directory.
+With a decrypted and decompressed regulation, also reports the patches that
+match no rows.";
+
+fn main() -> Result<(), String> {
+    let mut args = std::env::args().skip(1);
+    let mut paths = Vec::new();
+    let mut regulation = None;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--regulation" => {
+                let path = args.next().ok_or_else(|| USAGE.to_string())?;
+                regulation = Some(Regulation::read(path.as_ref())?);
+            },
+            "--help" | "-h" => return Err(USAGE.to_string()),
+            _ => paths.push(PathBuf::from(arg)),
+        }
+    }
+
+    if paths.is_empty() {
+        paths = patch_set::discover(&std::env::current_dir().map_err(|e| e.to_string())?)?;
+    }
+
+    if paths.is_empty() {
+        return Err(USAGE.to_string());
+    }
+
+    let (patch_set, errors) = PatchSet::load(&paths);
+
+    for file in patch_set.files() {
+        println!("{}: ok", file.path.display());
+
+        let Some(regulation) = &regulation else {
+            continue;
+        };
+
+        for patch in file.config.patches() {
+            let count = regulation
+                .param(&patch.param)
+                .map(|param| {
+                    param.ids().filter(|&id| patch.selector.matches(&patch.param, id)).count()
+                })
+                .unwrap_or(0);
+
+            if count == 0 {
+                println!("  Warning: [{}.\"{}\"] matches no rows", patch.param, patch.selector);
+            } else {
+                println!("  [{}.\"{}\"] matches {count} row(s)", patch.param, patch.selector);
+            }
+        }
+    }
+
+    for e in &errors {
+        eprintln!("{e}");
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(format!("{} error(s) found", errors.len()))
+    }
+}
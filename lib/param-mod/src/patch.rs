@@ -0,0 +1,874 @@
+//! Patch file language.
+//!
+//! A patch file is a TOML document whose tables select param rows and whose
+//! keys edit fields of the selected rows:
+//!
+//! ```toml
+//! # Exact row id
+//! [ItemLotParam.11700000]
+//! lot_item_base_point01 = 0
+//!
+//! # Id range (`..` excludes the end, `..=` includes it)
+//! [EquipParamWeapon."2000000..2999999"]
+//! weight = "*= 0.5"
+//!
+//! # Row name from the Paramdex names, `*` matches any sequence of characters
+//! [EquipParamWeapon."name:*Dagger*"]
+//! atk_base_physics = "+= 10"
+//!
+//! # Every row
+//! [EquipParamProtector."*"]
+//! defense_phys = 100
+//! ```
+//!
+//! Values are either literals (assignments) or strings containing a relative
+//! edit: `+= n`, `-= n`, `*= n`, `/= n`. Any other string is the name of a
+//! value of the field's Paramdex enum, e.g. `weapon_category = "Dagger"`.
+//!
+//! The tables of a param are applied in the order they appear in the file,
+//! so a table overrides the fields edited by the tables above it.
+//!
+//! Parsing and validation don't need the game to be running.
+
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::ops::RangeInclusive;
+
+use libds3::prelude::*;
+use toml::Value;
+
+use crate::vanilla::Vanilla;
+
+/// A parsed patch file.
+#[derive(Debug)]
+pub struct PatchConfig(Vec<ParamPatch>);
+
+/// A set of edits applied to the rows of a param matched by a selector.
+#[derive(Debug)]
+pub struct ParamPatch {
+    pub param: String,
+    pub selector: RowSelector,
+    pub edits: Vec<FieldEdit>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum RowSelector {
+    Id(u64),
+    Range(RangeInclusive<u64>),
+    Name(String),
+    All,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldEdit {
+    pub field: String,
+    pub op: EditOp,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum EditOp {
+    Set(Literal),
+    Add(Literal),
+    Sub(Literal),
+    Mul(Literal),
+    Div(Literal),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Literal {
+    Int(i64),
+    Float(f64),
+    Bool(bool),
+    /// An enum value name, resolved against the field's metadata.
+    Name(String),
+}
+
+/// The type of a param field, as seen by a [`ParamVisitor`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FieldKind {
+    Int { min: i64, max: i64 },
+    Float,
+    Bool,
+}
+
+impl PatchConfig {
+    /// Parses a patch file, keeping the tables in file order. All syntax
+    /// errors are reported at once.
+    pub fn parse(s: &str) -> Result<PatchConfig, Vec<String>> {
+        let value: Value = toml::from_str(s).map_err(|e| vec![format!("TOML parse error: {e}")])?;
+
+        let Value::Table(params) = value else {
+            return Err(vec!["Expected a table of params".to_string()]);
+        };
+
+        let mut patches = Vec::new();
+        let mut errors = Vec::new();
+
+        for (param, rows) in params {
+            let Value::Table(rows) = rows else {
+                errors.push(format!("[{param}] expected a table of rows"));
+                continue;
+            };
+
+            for (row, fields) in rows {
+                let location = format!("[{param}.\"{row}\"]");
+
+                let selector = match RowSelector::parse(&row) {
+                    Ok(selector) => selector,
+                    Err(e) => {
+                        errors.push(format!("{location} {e}"));
+                        continue;
+                    },
+                };
+
+                let Value::Table(fields) = fields else {
+                    errors.push(format!("{location} expected a table of fields"));
+                    continue;
+                };
+
+                let mut edits = Vec::new();
+                for (field, value) in fields {
+                    match EditOp::parse(&value) {
+                        Ok(op) => edits.push(FieldEdit { field, op }),
+                        Err(e) => errors.push(format!("{location} {field}: {e}")),
+                    }
+                }
+
+                patches.push(ParamPatch { param: param.clone(), selector, edits });
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(PatchConfig(patches))
+        } else {
+            Err(errors)
+        }
+    }
+
+    pub fn patches(&self) -> &[ParamPatch] {
+        &self.0
+    }
+
+    /// Checks that all params and fields exist and that every edit is
+    /// compatible with the field's type.
+    pub fn validate(&self) -> Result<(), Vec<String>> {
+        let mut layouts: HashMap<&str, Option<LayoutCollector>> = HashMap::new();
+        let mut errors = Vec::new();
+
+        for patch in &self.0 {
+            let layout = layouts.entry(patch.param.as_str()).or_insert_with(|| {
+                let mut collector = LayoutCollector::default();
+                visit_param_layout(&patch.param, &mut collector).then_some(collector)
+            });
+
+            let Some(layout) = layout else {
+                errors.push(format!("[{}] unknown param", patch.param));
+                continue;
+            };
+
+            for edit in &patch.edits {
+                let location = format!("[{}.\"{}\"] {}", patch.param, patch.selector, edit.field);
+                let Some(kind) = layout.kinds.get(&edit.field) else {
+                    errors.push(format!("{location}: unknown field"));
+                    continue;
+                };
+
+                let result = match &edit.op {
+                    EditOp::Set(Literal::Name(name)) => {
+                        check_enum_name(layout.metas.get(&edit.field), name)
+                    },
+                    op => op.check(*kind),
+                };
+
+                if let Err(e) = result {
+                    errors.push(format!("{location}: {e}"));
+                }
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+impl ParamPatch {
+    /// Applies the edits to every selected row, recording the vanilla values
+    /// of the edited fields first. Returns the ids of the rows that were
+    /// patched.
+    pub fn apply(&self, params: &Params, vanilla: &mut Vanilla) -> Vec<u64> {
+        let idx_ids: Vec<(usize, u64)> =
+            match (&self.selector, unsafe { params.iter_param_ids(&self.param) }) {
+                (_, None) => {
+                    eprintln!("  Couldn't iter param ids: {}", self.param);
+                    Vec::new()
+                },
+                // Single rows are looked up in the index instead of scanning
+                // the whole param.
+                (&RowSelector::Id(id), Some(_)) => {
+                    params.index_of(&self.param, id).map(|idx| (idx, id)).into_iter().collect()
+                },
+                (selector, Some(it)) => {
+                    it.enumerate().filter(|(_, id)| selector.matches(&self.param, *id)).collect()
+                },
+            };
+
+        idx_ids
+            .into_iter()
+            .map(|(param_idx, id)| {
+                println!("  Visiting {}:{param_idx} ({id})", self.param);
+                vanilla.capture(
+                    params,
+                    &self.param,
+                    param_idx,
+                    self.edits.iter().map(|e| e.field.as_str()),
+                );
+                params.visit_param_item(
+                    &self.param,
+                    param_idx,
+                    &mut PatchApplier::new(&self.edits),
+                );
+                id
+            })
+            .collect()
+    }
+}
+
+impl RowSelector {
+    pub fn parse(s: &str) -> Result<RowSelector, String> {
+        let s = s.trim();
+
+        if s == "*" {
+            return Ok(RowSelector::All);
+        }
+
+        if let Some(pattern) = s.strip_prefix("name:") {
+            return Ok(RowSelector::Name(pattern.trim().to_string()));
+        }
+
+        let parse_id =
+            |s: &str| s.trim().parse::<u64>().map_err(|_| format!("invalid row id \"{s}\""));
+
+        let range = if let Some((start, end)) = s.split_once("..=") {
+            Some(parse_id(start)?..=parse_id(end)?)
+        } else if let Some((start, end)) = s.split_once("..") {
+            let (start, end) = (parse_id(start)?, parse_id(end)?);
+            if end <= start {
+                return Err(format!("empty row range \"{s}\""));
+            }
+            Some(start..=end - 1)
+        } else {
+            None
+        };
+
+        match range {
+            Some(range) if range.is_empty() => Err(format!("empty row range \"{s}\"")),
+            Some(range) => Ok(RowSelector::Range(range)),
+            None => Ok(RowSelector::Id(parse_id(s)?)),
+        }
+    }
+
+    /// Whether the row `id` of `param` is selected.
+    pub fn matches(&self, param: &str, id: u64) -> bool {
+        match self {
+            RowSelector::Id(i) => *i == id,
+            RowSelector::Range(range) => range.contains(&id),
+            RowSelector::All => true,
+            RowSelector::Name(pattern) => PARAM_NAMES
+                .get(param)
+                .and_then(|names| names.get(&(id as usize)))
+                .map(|name| glob_match(pattern, name))
+                .unwrap_or(false),
+        }
+    }
+}
+
+impl Display for RowSelector {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RowSelector::Id(id) => write!(f, "{id}"),
+            RowSelector::Range(range) => write!(f, "{}..={}", range.start(), range.end()),
+            RowSelector::Name(pattern) => write!(f, "name:{pattern}"),
+            RowSelector::All => write!(f, "*"),
+        }
+    }
+}
+
+/// Case-insensitive match where `*` in the pattern matches any sequence of
+/// characters.
+fn glob_match(pattern: &str, s: &str) -> bool {
+    let pattern = pattern.to_lowercase();
+    let s = s.to_lowercase();
+
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+    let Some(mut rest) = s.strip_prefix(first) else {
+        return false;
+    };
+
+    let mut parts = parts.collect::<Vec<_>>();
+    let Some(last) = parts.pop() else {
+        // No wildcards: the pattern must match the whole string.
+        return rest.is_empty();
+    };
+
+    for part in parts {
+        match rest.find(part) {
+            Some(idx) => rest = &rest[idx + part.len()..],
+            None => return false,
+        }
+    }
+
+    rest.ends_with(last)
+}
+
+impl EditOp {
+    pub fn parse(value: &Value) -> Result<EditOp, String> {
+        match value {
+            Value::Integer(i) => Ok(EditOp::Set(Literal::Int(*i))),
+            Value::Float(f) => Ok(EditOp::Set(Literal::Float(*f))),
+            Value::Boolean(b) => Ok(EditOp::Set(Literal::Bool(*b))),
+            Value::String(s) => {
+                let s = s.trim();
+                let (op, operand): (fn(Literal) -> EditOp, _) =
+                    if let Some(o) = s.strip_prefix("+=") {
+                        (EditOp::Add, o)
+                    } else if let Some(o) = s.strip_prefix("-=") {
+                        (EditOp::Sub, o)
+                    } else if let Some(o) = s.strip_prefix("*=") {
+                        (EditOp::Mul, o)
+                    } else if let Some(o) = s.strip_prefix("/=") {
+                        (EditOp::Div, o)
+                    } else {
+                        return Ok(EditOp::Set(Literal::Name(s.to_string())));
+                    };
+
+                Ok(op(Literal::parse_number(operand.trim())?))
+            },
+            other => Err(format!("unsupported value {other}")),
+        }
+    }
+
+    fn operand(&self) -> &Literal {
+        match self {
+            EditOp::Set(l) | EditOp::Add(l) | EditOp::Sub(l) | EditOp::Mul(l) | EditOp::Div(l) => l,
+        }
+    }
+
+    /// Replaces an enum value name with its value. Other edits are returned
+    /// unchanged.
+    pub fn resolve(&self, meta: Option<&FieldMeta>) -> Result<EditOp, String> {
+        match self {
+            EditOp::Set(Literal::Name(name)) => meta
+                .and_then(|meta| meta.enum_value(name))
+                .map(|value| EditOp::Set(Literal::Int(value)))
+                .ok_or_else(|| format!("unknown enum value \"{name}\"")),
+            op => Ok(op.clone()),
+        }
+    }
+
+    /// Checks whether the edit can be applied to a field of the given kind.
+    pub fn check(&self, kind: FieldKind) -> Result<(), String> {
+        let operand = self.operand();
+
+        match (self, kind, operand) {
+            (EditOp::Set(_), FieldKind::Int { min, max }, &Literal::Int(i)) => {
+                if (min..=max).contains(&i) {
+                    Ok(())
+                } else {
+                    Err(format!("{i} out of range {min}..={max}"))
+                }
+            },
+            (EditOp::Set(_), FieldKind::Float, Literal::Float(_))
+            | (EditOp::Set(_), FieldKind::Bool, Literal::Bool(_))
+            | (EditOp::Add(_) | EditOp::Sub(_), FieldKind::Int { .. }, Literal::Int(_))
+            | (EditOp::Add(_) | EditOp::Sub(_), FieldKind::Float, Literal::Float(_))
+            | (
+                EditOp::Mul(_) | EditOp::Div(_),
+                FieldKind::Int { .. } | FieldKind::Float,
+                Literal::Int(_) | Literal::Float(_),
+            ) => {
+                if matches!(self, EditOp::Div(_)) && operand.is_zero() {
+                    Err("division by zero".to_string())
+                } else {
+                    Ok(())
+                }
+            },
+            (_, kind, operand) => {
+                Err(format!("type mismatch: {} given for {} field", operand.kind_name(), kind))
+            },
+        }
+    }
+
+    /// Computes the new value of an integer field. The result is clamped to
+    /// `min..=max`. Returns `None` if the edit doesn't apply to integers.
+    pub fn apply_int(&self, current: i64, min: i64, max: i64) -> Option<i64> {
+        let value = match (self, self.operand()) {
+            (EditOp::Set(_), &Literal::Int(i)) => i,
+            (EditOp::Add(_), &Literal::Int(i)) => current.saturating_add(i),
+            (EditOp::Sub(_), &Literal::Int(i)) => current.saturating_sub(i),
+            (EditOp::Mul(_), &Literal::Int(i)) => current.saturating_mul(i),
+            (EditOp::Div(_), &Literal::Int(i)) if i != 0 => current / i,
+            (EditOp::Mul(_), &Literal::Float(f)) => (current as f64 * f).round() as i64,
+            (EditOp::Div(_), &Literal::Float(f)) if f != 0.0 => (current as f64 / f).round() as i64,
+            _ => return None,
+        };
+
+        Some(value.clamp(min, max))
+    }
+
+    /// Computes the new value of a float field. Returns `None` if the edit
+    /// doesn't apply to floats.
+    pub fn apply_float(&self, current: f32) -> Option<f32> {
+        let current = current as f64;
+        let value = match (self, self.operand()) {
+            (EditOp::Set(_), &Literal::Float(f)) => f,
+            (EditOp::Add(_), &Literal::Float(f)) => current + f,
+            (EditOp::Sub(_), &Literal::Float(f)) => current - f,
+            (EditOp::Mul(_), &Literal::Float(f)) => current * f,
+            (EditOp::Mul(_), &Literal::Int(i)) => current * i as f64,
+            (EditOp::Div(_), &Literal::Float(f)) if f != 0.0 => current / f,
+            (EditOp::Div(_), &Literal::Int(i)) if i != 0 => current / i as f64,
+            _ => return None,
+        };
+
+        Some(value as f32)
+    }
+
+    /// Computes the new value of a boolean field. Returns `None` if the edit
+    /// doesn't apply to booleans.
+    pub fn apply_bool(&self) -> Option<bool> {
+        match self {
+            EditOp::Set(Literal::Bool(b)) => Some(*b),
+            _ => None,
+        }
+    }
+}
+
+impl Literal {
+    fn parse_number(s: &str) -> Result<Literal, String> {
+        if let Ok(i) = s.parse::<i64>() {
+            Ok(Literal::Int(i))
+        } else if let Ok(f) = s.parse::<f64>() {
+            Ok(Literal::Float(f))
+        } else {
+            Err(format!("invalid number \"{s}\""))
+        }
+    }
+
+    fn is_zero(&self) -> bool {
+        match self {
+            Literal::Int(i) => *i == 0,
+            Literal::Float(f) => *f == 0.0,
+            Literal::Bool(_) | Literal::Name(_) => false,
+        }
+    }
+
+    fn kind_name(&self) -> &'static str {
+        match self {
+            Literal::Int(_) => "integer",
+            Literal::Float(_) => "float",
+            Literal::Bool(_) => "boolean",
+            Literal::Name(_) => "enum value",
+        }
+    }
+}
+
+/// Checks that `name` is a value of the field's enum.
+fn check_enum_name(meta: Option<&FieldMeta>, name: &str) -> Result<(), String> {
+    let Some(values) = meta.and_then(FieldMeta::enum_values) else {
+        if PARAM_ENUMS.is_empty() {
+            return Err(format!(
+                "\"{name}\" given as an enum value, but the param data was generated without \
+                 Paramdex enum metadata"
+            ));
+        }
+        return Err(format!("\"{name}\" given for a field without enum values"));
+    };
+
+    if values.iter().any(|(_, n)| n.eq_ignore_ascii_case(name)) {
+        Ok(())
+    } else {
+        let names = values.iter().map(|(_, n)| *n).collect::<Vec<_>>().join(", ");
+        Err(format!("unknown enum value \"{name}\", expected one of: {names}"))
+    }
+}
+
+impl Display for FieldKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FieldKind::Int { min, max } => write!(f, "integer ({min}..={max})"),
+            FieldKind::Float => write!(f, "float"),
+            FieldKind::Bool => write!(f, "boolean"),
+        }
+    }
+}
+
+/// Collects the name, kind and metadata of every visited field.
+#[derive(Default)]
+struct LayoutCollector {
+    kinds: HashMap<String, FieldKind>,
+    metas: HashMap<String, FieldMeta>,
+}
+
+impl LayoutCollector {
+    fn int(&mut self, name: &str, min: i64, max: i64) {
+        self.kinds.insert(name.to_string(), FieldKind::Int { min, max });
+    }
+}
+
+impl ParamVisitor for LayoutCollector {
+    fn visit_meta(&mut self, name: &str, meta: &FieldMeta) {
+        self.metas.insert(name.to_string(), *meta);
+    }
+
+    fn visit_u8(&mut self, name: &str, _: &mut u8) {
+        self.int(name, u8::MIN as _, u8::MAX as _);
+    }
+
+    fn visit_u16(&mut self, name: &str, _: &mut u16) {
+        self.int(name, u16::MIN as _, u16::MAX as _);
+    }
+
+    fn visit_u32(&mut self, name: &str, _: &mut u32) {
+        self.int(name, u32::MIN as _, u32::MAX as _);
+    }
+
+    fn visit_i8(&mut self, name: &str, _: &mut i8) {
+        self.int(name, i8::MIN as _, i8::MAX as _);
+    }
+
+    fn visit_i16(&mut self, name: &str, _: &mut i16) {
+        self.int(name, i16::MIN as _, i16::MAX as _);
+    }
+
+    fn visit_i32(&mut self, name: &str, _: &mut i32) {
+        self.int(name, i32::MIN as _, i32::MAX as _);
+    }
+
+    fn visit_f32(&mut self, name: &str, _: &mut f32) {
+        self.kinds.insert(name.to_string(), FieldKind::Float);
+    }
+
+    fn visit_bool(&mut self, name: &str, _: &mut bool) {
+        self.kinds.insert(name.to_string(), FieldKind::Bool);
+    }
+}
+
+/// Applies a list of edits to a param row.
+pub struct PatchApplier<'a> {
+    edits: &'a [FieldEdit],
+    meta: Option<(String, FieldMeta)>,
+}
+
+impl<'a> PatchApplier<'a> {
+    pub fn new(edits: &'a [FieldEdit]) -> Self {
+        Self { edits, meta: None }
+    }
+
+    fn edits_for<'b>(&'b self, name: &'b str) -> impl Iterator<Item = &'a EditOp> + 'b {
+        self.edits.iter().filter(move |e| e.field == name).map(|e| &e.op)
+    }
+
+    /// Metadata of the field `name`, if it was visited right before it.
+    fn meta(&self, name: &str) -> Option<&FieldMeta> {
+        self.meta.as_ref().filter(|(n, _)| n == name).map(|(_, meta)| meta)
+    }
+
+    fn int(&self, name: &str, current: i64, min: i64, max: i64) -> Option<i64> {
+        self.edits_for(name).fold(None, |value, op| {
+            let applied = match op.resolve(self.meta(name)) {
+                Ok(op) => op.apply_int(value.unwrap_or(current), min, max),
+                Err(e) => {
+                    eprintln!("    Couldn't apply {op:?} to {name}: {e}");
+                    return value;
+                },
+            };
+            match applied {
+                Some(v) => println!("    Applied {name} := {v}"),
+                None => eprintln!("    Couldn't apply {op:?} to {name}"),
+            }
+            applied.or(value)
+        })
+    }
+}
+
+impl ParamVisitor for PatchApplier<'_> {
+    fn visit_meta(&mut self, name: &str, meta: &FieldMeta) {
+        self.meta = Some((name.to_string(), *meta));
+    }
+
+    fn visit_u8(&mut self, name: &str, v: &mut u8) {
+        if let Some(i) = self.int(name, *v as _, u8::MIN as _, u8::MAX as _) {
+            *v = i as _;
+        }
+    }
+
+    fn visit_u16(&mut self, name: &str, v: &mut u16) {
+        if let Some(i) = self.int(name, *v as _, u16::MIN as _, u16::MAX as _) {
+            *v = i as _;
+        }
+    }
+
+    fn visit_u32(&mut self, name: &str, v: &mut u32) {
+        if let Some(i) = self.int(name, *v as _, u32::MIN as _, u32::MAX as _) {
+            *v = i as _;
+        }
+    }
+
+    fn visit_i8(&mut self, name: &str, v: &mut i8) {
+        if let Some(i) = self.int(name, *v as _, i8::MIN as _, i8::MAX as _) {
+            *v = i as _;
+        }
+    }
+
+    fn visit_i16(&mut self, name: &str, v: &mut i16) {
+        if let Some(i) = self.int(name, *v as _, i16::MIN as _, i16::MAX as _) {
+            *v = i as _;
+        }
+    }
+
+    fn visit_i32(&mut self, name: &str, v: &mut i32) {
+        if let Some(i) = self.int(name, *v as _, i32::MIN as _, i32::MAX as _) {
+            *v = i as _;
+        }
+    }
+
+    fn visit_f32(&mut self, name: &str, v: &mut f32) {
+        for op in self.edits_for(name) {
+            match op.apply_float(*v) {
+                Some(f) => {
+                    println!("    Applied {name} := {f}");
+                    *v = f;
+                },
+                None => eprintln!("    Couldn't apply {op:?} to {name}"),
+            }
+        }
+    }
+
+    fn visit_bool(&mut self, name: &str, v: &mut bool) {
+        for op in self.edits_for(name) {
+            match op.apply_bool() {
+                Some(b) => {
+                    println!("    Applied {name} := {b}");
+                    *v = b;
+                },
+                None => eprintln!("    Couldn't apply {op:?} to {name}"),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_row_selector() {
+        assert_eq!(RowSelector::parse("2000000"), Ok(RowSelector::Id(2000000)));
+        assert_eq!(RowSelector::parse("*"), Ok(RowSelector::All));
+        assert_eq!(RowSelector::parse("10..20"), Ok(RowSelector::Range(10..=19)));
+        assert_eq!(RowSelector::parse("10..=20"), Ok(RowSelector::Range(10..=20)));
+        assert_eq!(RowSelector::parse("name:*Dagger"), Ok(RowSelector::Name("*Dagger".into())));
+        assert!(RowSelector::parse("20..10").is_err());
+        assert!(RowSelector::parse("10..10").is_err());
+        assert!(RowSelector::parse("abc").is_err());
+        assert!(RowSelector::parse("-1").is_err());
+
+        let range = RowSelector::parse("2000000..2999999").unwrap();
+        assert!(range.matches("EquipParamWeapon", 2000000));
+        assert!(range.matches("EquipParamWeapon", 2999998));
+        assert!(!range.matches("EquipParamWeapon", 2999999));
+    }
+
+    #[test]
+    fn test_glob_match() {
+        assert!(glob_match("dagger", "Dagger"));
+        assert!(!glob_match("dagger", "Dagger+1"));
+        assert!(glob_match("*dagger*", "Handmaid's Dagger+1"));
+        assert!(glob_match("*dagger", "Handmaid's Dagger"));
+        assert!(glob_match("hand*dagger", "Handmaid's Dagger"));
+        assert!(!glob_match("hand*dagger", "Handmaid's Dagger+1"));
+        assert!(glob_match("*", ""));
+        assert!(!glob_match("a*a", "a"));
+    }
+
+    #[test]
+    fn test_edit_op_parse() {
+        assert_eq!(EditOp::parse(&Value::Integer(3)), Ok(EditOp::Set(Literal::Int(3))));
+        assert_eq!(EditOp::parse(&Value::Boolean(true)), Ok(EditOp::Set(Literal::Bool(true))));
+        assert_eq!(EditOp::parse(&Value::from("*= 1.5")), Ok(EditOp::Mul(Literal::Float(1.5))));
+        assert_eq!(EditOp::parse(&Value::from("+=10")), Ok(EditOp::Add(Literal::Int(10))));
+        assert_eq!(EditOp::parse(&Value::from("-= -2")), Ok(EditOp::Sub(Literal::Int(-2))));
+        assert_eq!(EditOp::parse(&Value::from("/= 2")), Ok(EditOp::Div(Literal::Int(2))));
+        assert_eq!(
+            EditOp::parse(&Value::from(" Dagger ")),
+            Ok(EditOp::Set(Literal::Name("Dagger".into())))
+        );
+        assert!(EditOp::parse(&Value::from("+= x")).is_err());
+    }
+
+    #[test]
+    fn test_edit_op_apply() {
+        assert_eq!(EditOp::Set(Literal::Int(5)).apply_int(1, 0, 255), Some(5));
+        assert_eq!(EditOp::Add(Literal::Int(10)).apply_int(250, 0, 255), Some(255));
+        assert_eq!(EditOp::Sub(Literal::Int(10)).apply_int(5, 0, 255), Some(0));
+        assert_eq!(EditOp::Mul(Literal::Float(1.5)).apply_int(101, 0, 255), Some(152));
+        assert_eq!(EditOp::Div(Literal::Int(2)).apply_int(101, 0, 255), Some(50));
+        assert_eq!(EditOp::Set(Literal::Float(1.0)).apply_int(101, 0, 255), None);
+
+        assert_eq!(EditOp::Mul(Literal::Float(1.5)).apply_float(2.0), Some(3.0));
+        assert_eq!(EditOp::Mul(Literal::Int(2)).apply_float(2.0), Some(4.0));
+        assert_eq!(EditOp::Add(Literal::Float(0.5)).apply_float(2.0), Some(2.5));
+        assert_eq!(EditOp::Add(Literal::Int(1)).apply_float(2.0), None);
+
+        assert_eq!(EditOp::Set(Literal::Bool(true)).apply_bool(), Some(true));
+        assert_eq!(EditOp::Set(Literal::Int(1)).apply_bool(), None);
+    }
+
+    #[test]
+    fn test_edit_op_check() {
+        let u8_kind = FieldKind::Int { min: 0, max: 255 };
+        assert!(EditOp::Set(Literal::Int(255)).check(u8_kind).is_ok());
+        assert!(EditOp::Set(Literal::Int(256)).check(u8_kind).is_err());
+        assert!(EditOp::Set(Literal::Float(1.0)).check(u8_kind).is_err());
+        assert!(EditOp::Mul(Literal::Float(1.5)).check(u8_kind).is_ok());
+        assert!(EditOp::Div(Literal::Int(0)).check(u8_kind).is_err());
+        assert!(EditOp::Set(Literal::Int(1)).check(FieldKind::Float).is_err());
+        assert!(EditOp::Add(Literal::Int(1)).check(FieldKind::Float).is_err());
+        assert!(EditOp::Set(Literal::Int(1)).check(FieldKind::Bool).is_err());
+        assert!(EditOp::Add(Literal::Bool(true)).check(FieldKind::Bool).is_err());
+        assert!(EditOp::Set(Literal::Name("Dagger".into())).check(u8_kind).is_err());
+    }
+
+    #[test]
+    fn test_edit_op_resolve() {
+        let op = EditOp::Set(Literal::Name("Dagger".into()));
+        assert!(op.resolve(None).is_err());
+        assert!(op.resolve(Some(&FieldMeta::default())).is_err());
+        assert!(check_enum_name(Some(&FieldMeta::default()), "Dagger").is_err());
+
+        let op = EditOp::Add(Literal::Int(1));
+        assert_eq!(op.resolve(None), Ok(op.clone()));
+    }
+
+    #[test]
+    fn test_parse_and_validate() {
+        let config = PatchConfig::parse(
+            r#"
+            [EquipParamWeapon."2000000..2999999"]
+            weight = "*= 0.5"
+            sort_id = 10
+
+            [ItemLotParam.11700000]
+            lot_item_base_point01 = 0
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(config.patches().len(), 2);
+        config.validate().unwrap();
+    }
+
+    #[test]
+    fn test_parse_order() {
+        let config = PatchConfig::parse(
+            r#"
+            [EquipParamWeapon."name:*"]
+            sort_id = 1
+
+            [EquipParamWeapon.2000000]
+            sort_id = 2
+
+            [ItemLotParam.11700000]
+            lot_item_base_point01 = 0
+
+            [EquipParamWeapon."*"]
+            sort_id = 3
+            "#,
+        )
+        .unwrap();
+
+        let selectors = config.patches().iter().map(|p| p.selector.to_string()).collect::<Vec<_>>();
+        // Tables of the same param are grouped, in file order.
+        assert_eq!(selectors, ["name:*", "2000000", "*", "11700000"]);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let errors = PatchConfig::parse(
+            r#"
+            [EquipParamWeapon.abc]
+            weight = 1.0
+
+            [EquipParamWeapon.2000000]
+            weight = [1, 2]
+            sort_id = "+= x"
+            "#,
+        )
+        .unwrap_err();
+
+        assert_eq!(errors.len(), 3);
+    }
+
+    #[test]
+    fn test_validate_errors() {
+        let config = PatchConfig::parse(
+            r#"
+            [NotAParam.1]
+            weight = 1.0
+
+            [EquipParamWeapon.2000000]
+            weight = 1
+            not_a_field = 1
+            sort_id = 1.5
+            "#,
+        )
+        .unwrap();
+
+        let errors = config.validate().unwrap_err();
+        assert_eq!(errors.len(), 4, "{errors:#?}");
+        assert!(errors.iter().any(|e| e.contains("unknown param")));
+        assert!(errors.iter().any(|e| e.contains("not_a_field: unknown field")));
+        assert!(errors.iter().any(|e| e.contains("weight: type mismatch")));
+        assert!(errors.iter().any(|e| e.contains("sort_id: type mismatch")));
+    }
+
+    #[test]
+    fn test_patch_applier() {
+        let mut row: EquipParamWeapon = unsafe { std::mem::zeroed() };
+        row.weight = 2.0;
+        row.sort_id = 10;
+
+        let edits = [
+            FieldEdit { field: "weight".into(), op: EditOp::Mul(Literal::Float(1.5)) },
+            FieldEdit { field: "sort_id".into(), op: EditOp::Add(Literal::Int(5)) },
+            FieldEdit { field: "sort_id".into(), op: EditOp::Mul(Literal::Int(2)) },
+        ];
+        row.visit(&mut PatchApplier::new(&edits));
+
+        assert_eq!(row.weight, 3.0);
+        assert_eq!(row.sort_id, 30);
+    }
+
+    #[test]
+    fn test_bitfield() {
+        let config = PatchConfig::parse(
+            r#"
+            [EquipParamProtector."*"]
+            disable_multi_drop_share = true
+            "#,
+        )
+        .unwrap();
+        config.validate().unwrap();
+
+        let mut row: EquipParamProtector = unsafe { std::mem::zeroed() };
+        row.bitfield6 = 0b0000_0001;
+        row.visit(&mut PatchApplier::new(&config.patches()[0].edits));
+
+        assert!(row.disable_multi_drop_share());
+        assert_eq!(row.bitfield6, 0b0100_0001);
+    }
+}
@@ -0,0 +1,383 @@
+//! Ordered, layered patch files.
+//!
+//! Patch files are looked up in the directory of the DLL:
+//!
+//! - if `param-mod.index` exists, each non-empty line that doesn't start with
+//!   `#` is the path of a patch file, relative to that directory;
+//! - otherwise, `param-mod.toml` is loaded first, followed by every `*.toml`
+//!   file in the `param-mod` directory, sorted by file name.
+//!
+//! Files are applied in order, and so are the tables of each file, so later
+//! edits override earlier ones.
+
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use libds3::prelude::*;
+
+use crate::patch::PatchConfig;
+use crate::vanilla::Vanilla;
+
+pub const INDEX_FILE: &str = "param-mod.index";
+pub const PATCH_FILE: &str = "param-mod.toml";
+pub const PATCH_DIR: &str = "param-mod";
+
+/// Returns the ordered list of patch files found in `dir`.
+pub fn discover(dir: &Path) -> Result<Vec<PathBuf>, String> {
+    let index_path = dir.join(INDEX_FILE);
+
+    if index_path.is_file() {
+        let index = std::fs::read_to_string(&index_path)
+            .map_err(|e| format!("Couldn't read {}: {e}", index_path.display()))?;
+
+        return Ok(index
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(|line| dir.join(line))
+            .collect());
+    }
+
+    let mut paths = Vec::new();
+
+    let patch_file = dir.join(PATCH_FILE);
+    if patch_file.is_file() {
+        paths.push(patch_file);
+    }
+
+    let patch_dir = dir.join(PATCH_DIR);
+    if patch_dir.is_dir() {
+        let mut dir_paths = std::fs::read_dir(&patch_dir)
+            .map_err(|e| format!("Couldn't read {}: {e}", patch_dir.display()))?
+            .filter_map(|e| e.ok())
+            .map(|e| e.path())
+            .filter(|p| p.is_file() && p.extension().map(|ext| ext == "toml").unwrap_or(false))
+            .collect::<Vec<_>>();
+        dir_paths.sort();
+        paths.extend(dir_paths);
+    }
+
+    Ok(paths)
+}
+
+pub struct PatchFile {
+    pub path: PathBuf,
+    pub config: PatchConfig,
+}
+
+/// Patch files that were successfully loaded, in application order.
+#[derive(Default)]
+pub struct PatchSet(Vec<PatchFile>);
+
+impl PatchSet {
+    /// Loads and validates every file. Files that can't be read, parsed or
+    /// validated are skipped, and their errors are returned.
+    pub fn load(paths: &[PathBuf]) -> (PatchSet, Vec<String>) {
+        let mut files = Vec::new();
+        let mut errors = Vec::new();
+
+        for path in paths {
+            let config = std::fs::read_to_string(path)
+                .map_err(|e| vec![format!("Couldn't read file: {e}")])
+                .and_then(|content| PatchConfig::parse(&content))
+                .and_then(|config| config.validate().map(|_| config));
+
+            match config {
+                Ok(config) => files.push(PatchFile { path: path.clone(), config }),
+                Err(e) => errors.extend(e.into_iter().map(|e| format!("{}: {e}", path.display()))),
+            }
+        }
+
+        (PatchSet(files), errors)
+    }
+
+    pub fn files(&self) -> &[PatchFile] {
+        &self.0
+    }
+
+    /// Applies all files in order, recording which fields are edited by more
+    /// than one table, in the same file or not.
+    pub fn apply(&self, params: &Params, vanilla: &mut Vanilla) -> PatchSummary {
+        let mut summary = PatchSummary { files: self.0.len(), ..Default::default() };
+        let mut conflicts = ConflictTracker::default();
+
+        for (file_idx, file) in self.0.iter().enumerate() {
+            println!("Applying {}", file.path.display());
+
+            for (patch_idx, patch) in file.config.patches().iter().enumerate() {
+                println!("Applying to {}.\"{}\"", patch.param, patch.selector);
+                let ids = patch.apply(params, vanilla);
+
+                if ids.is_empty() {
+                    summary.unmatched.push(format!(
+                        "{}: [{}.\"{}\"] matched no rows",
+                        file.path.display(),
+                        patch.param,
+                        patch.selector
+                    ));
+                }
+
+                summary.rows += ids.len();
+                summary.fields += ids.len() * patch.edits.len();
+
+                for id in ids {
+                    for edit in &patch.edits {
+                        conflicts.record((file_idx, patch_idx), &patch.param, id, &edit.field);
+                    }
+                }
+            }
+        }
+
+        summary.conflicts = conflicts
+            .conflicts()
+            .map(|(&(ref param, ref field, earlier, later), rows)| {
+                format!(
+                    "{param}.{field} in {rows} row(s): {} overrides {}",
+                    self.describe(later),
+                    self.describe(earlier),
+                )
+            })
+            .collect();
+        summary.conflicts.sort();
+
+        summary
+    }
+
+    /// Location of a table, as the file path and the table's selector.
+    fn describe(&self, (file_idx, patch_idx): PatchSource) -> String {
+        let file = &self.0[file_idx];
+        let patch = &file.config.patches()[patch_idx];
+        format!("{} [{}.\"{}\"]", file.path.display(), patch.param, patch.selector)
+    }
+}
+
+/// Detects changes to the patch files of a directory by polling their
+/// modification times. Adding, removing or reordering files also counts as a
+/// change.
+pub struct PatchWatcher {
+    dir: PathBuf,
+    stamps: Vec<(PathBuf, Option<SystemTime>)>,
+}
+
+impl PatchWatcher {
+    pub fn new(dir: &Path) -> Self {
+        let mut watcher = PatchWatcher { dir: dir.to_path_buf(), stamps: Vec::new() };
+        watcher.stamps = watcher.stamps();
+        watcher
+    }
+
+    /// Whether anything changed since the last call.
+    pub fn changed(&mut self) -> bool {
+        let stamps = self.stamps();
+
+        if stamps != self.stamps {
+            self.stamps = stamps;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn stamps(&self) -> Vec<(PathBuf, Option<SystemTime>)> {
+        let mut paths = discover(&self.dir).unwrap_or_default();
+        paths.push(self.dir.join(INDEX_FILE));
+
+        paths
+            .into_iter()
+            .map(|path| {
+                let modified = std::fs::metadata(&path).and_then(|m| m.modified()).ok();
+                (path, modified)
+            })
+            .collect()
+    }
+}
+
+/// A table of a patch file, as the index of the file and of the table in it.
+type PatchSource = (usize, usize);
+
+/// Tracks which table last edited each field of each row.
+#[derive(Default)]
+struct ConflictTracker {
+    edits: HashMap<(String, u64, String), PatchSource>,
+    conflicts: HashMap<(String, String, PatchSource, PatchSource), usize>,
+}
+
+impl ConflictTracker {
+    fn record(&mut self, source: PatchSource, param: &str, id: u64, field: &str) {
+        let previous = self.edits.insert((param.to_string(), id, field.to_string()), source);
+
+        if let Some(previous) = previous.filter(|&previous| previous != source) {
+            *self
+                .conflicts
+                .entry((param.to_string(), field.to_string(), previous, source))
+                .or_default() += 1;
+        }
+    }
+
+    /// Conflicting edits as `(param, field, earlier table, later table)`, and
+    /// the number of rows affected.
+    fn conflicts(
+        &self,
+    ) -> impl Iterator<Item = (&(String, String, PatchSource, PatchSource), &usize)> {
+        self.conflicts.iter()
+    }
+}
+
+#[derive(Default)]
+pub struct PatchSummary {
+    pub files: usize,
+    pub rows: usize,
+    pub fields: usize,
+    pub unmatched: Vec<String>,
+    pub conflicts: Vec<String>,
+}
+
+impl Display for PatchSummary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "Applied {} file(s): {} field edit(s) in {} row(s)",
+            self.files, self.fields, self.rows
+        )?;
+
+        for unmatched in &self.unmatched {
+            writeln!(f, "  Warning: {unmatched}")?;
+        }
+
+        for conflict in &self.conflicts {
+            writeln!(f, "  Conflict: {conflict}")?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("param-mod-{name}-{}", std::process::id()));
+        std::fs::remove_dir_all(&dir).ok();
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_discover_dir() {
+        let dir = temp_dir("discover-dir");
+        std::fs::create_dir(dir.join(PATCH_DIR)).unwrap();
+        std::fs::write(dir.join(PATCH_FILE), "").unwrap();
+        std::fs::write(dir.join(PATCH_DIR).join("20-b.toml"), "").unwrap();
+        std::fs::write(dir.join(PATCH_DIR).join("10-a.toml"), "").unwrap();
+        std::fs::write(dir.join(PATCH_DIR).join("readme.txt"), "").unwrap();
+
+        assert_eq!(discover(&dir).unwrap(), vec![
+            dir.join(PATCH_FILE),
+            dir.join(PATCH_DIR).join("10-a.toml"),
+            dir.join(PATCH_DIR).join("20-b.toml"),
+        ]);
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_discover_index() {
+        let dir = temp_dir("discover-index");
+        std::fs::write(dir.join(PATCH_FILE), "").unwrap();
+        std::fs::write(dir.join(INDEX_FILE), "# Base\nbase.toml\n\n  drops/covenants.toml\n")
+            .unwrap();
+
+        assert_eq!(discover(&dir).unwrap(), vec![
+            dir.join("base.toml"),
+            dir.join("drops/covenants.toml")
+        ]);
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_discover_empty() {
+        let dir = temp_dir("discover-empty");
+        assert!(discover(&dir).unwrap().is_empty());
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_load() {
+        let dir = temp_dir("load");
+        std::fs::write(dir.join("good.toml"), "[ItemLotParam.1]\nlot_item_base_point01 = 0\n")
+            .unwrap();
+        std::fs::write(dir.join("bad.toml"), "[ItemLotParam.1]\nnot_a_field = 0\n").unwrap();
+
+        let (patch_set, errors) = PatchSet::load(&[
+            dir.join("good.toml"),
+            dir.join("bad.toml"),
+            dir.join("missing.toml"),
+        ]);
+
+        assert_eq!(patch_set.files().len(), 1);
+        assert_eq!(errors.len(), 2);
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_watcher() {
+        let dir = temp_dir("watcher");
+        let mut watcher = PatchWatcher::new(&dir);
+        assert!(!watcher.changed());
+
+        std::fs::write(dir.join(PATCH_FILE), "").unwrap();
+        assert!(watcher.changed());
+        assert!(!watcher.changed());
+
+        let file = std::fs::File::options().write(true).open(dir.join(PATCH_FILE)).unwrap();
+        file.set_modified(SystemTime::UNIX_EPOCH).unwrap();
+        assert!(watcher.changed());
+
+        std::fs::write(dir.join(INDEX_FILE), "other.toml\n").unwrap();
+        assert!(watcher.changed());
+
+        std::fs::remove_file(dir.join(INDEX_FILE)).unwrap();
+        assert!(watcher.changed());
+        assert!(!watcher.changed());
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_conflict_tracker() {
+        let mut tracker = ConflictTracker::default();
+        tracker.record((0, 0), "EquipParamWeapon", 1, "weight");
+        tracker.record((0, 0), "EquipParamWeapon", 2, "weight");
+        tracker.record((0, 0), "EquipParamWeapon", 1, "weight");
+        tracker.record((0, 0), "EquipParamWeapon", 1, "sort_id");
+        assert_eq!(tracker.conflicts().count(), 0);
+
+        tracker.record((1, 0), "EquipParamWeapon", 1, "weight");
+        tracker.record((1, 0), "EquipParamWeapon", 2, "weight");
+        tracker.record((1, 0), "EquipParamWeapon", 3, "weight");
+
+        let conflicts = tracker.conflicts().collect::<Vec<_>>();
+        assert_eq!(conflicts, vec![(
+            &("EquipParamWeapon".to_string(), "weight".to_string(), (0, 0), (1, 0)),
+            &2
+        )]);
+    }
+
+    #[test]
+    fn test_conflict_tracker_same_file() {
+        let mut tracker = ConflictTracker::default();
+        tracker.record((0, 0), "EquipParamWeapon", 1, "weight");
+        tracker.record((0, 1), "EquipParamWeapon", 1, "weight");
+
+        let conflicts = tracker.conflicts().collect::<Vec<_>>();
+        assert_eq!(conflicts, vec![(
+            &("EquipParamWeapon".to_string(), "weight".to_string(), (0, 0), (0, 1)),
+            &1
+        )]);
+    }
+}
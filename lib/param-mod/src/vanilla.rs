@@ -0,0 +1,156 @@
+//! Vanilla values of patched fields.
+//!
+//! The first time a field is about to be edited, its current value is
+//! recorded. Reverting writes every recorded value back, which lets a patch
+//! set be reapplied from scratch: relative edits don't compound, and fields
+//! that were removed from the patch files go back to their original values.
+
+use std::collections::HashMap;
+
+use libds3::prelude::*;
+
+/// Recorded values, by param name and row index.
+#[derive(Default)]
+pub struct Vanilla(HashMap<(String, usize), HashMap<String, FieldValue>>);
+
+impl Vanilla {
+    /// Records the current value of `fields` in a row, unless they were
+    /// already recorded.
+    pub fn capture<'a>(
+        &mut self,
+        params: &Params,
+        param: &str,
+        param_idx: usize,
+        fields: impl IntoIterator<Item = &'a str>,
+    ) {
+        let values = self.0.entry((param.to_string(), param_idx)).or_default();
+        let mut capture = FieldCapture { fields: fields.into_iter().collect(), values };
+        params.visit_param_item_ref(param, param_idx, &mut capture);
+    }
+
+    /// Writes every recorded value back to the params.
+    pub fn revert(&self, params: &Params) {
+        for ((param, param_idx), values) in &self.0 {
+            params.visit_param_item(param, *param_idx, &mut FieldRestore(values));
+        }
+    }
+
+    /// Number of recorded fields.
+    pub fn len(&self) -> usize {
+        self.0.values().map(HashMap::len).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Records the values of the given fields in a row.
+struct FieldCapture<'a> {
+    fields: Vec<&'a str>,
+    values: &'a mut HashMap<String, FieldValue>,
+}
+
+impl ParamRefVisitor for FieldCapture<'_> {
+    fn visit_field(&mut self, name: &'static str, value: FieldValue) {
+        if self.fields.contains(&name) {
+            self.values.entry(name.to_string()).or_insert(value);
+        }
+    }
+}
+
+/// Writes recorded values back to a row.
+struct FieldRestore<'a>(&'a HashMap<String, FieldValue>);
+
+impl FieldRestore<'_> {
+    fn int(&self, name: &str) -> Option<i64> {
+        self.0.get(name).and_then(FieldValue::as_i64)
+    }
+}
+
+impl ParamVisitor for FieldRestore<'_> {
+    fn visit_u8(&mut self, name: &str, v: &mut u8) {
+        if let Some(i) = self.int(name) {
+            *v = i as _;
+        }
+    }
+
+    fn visit_u16(&mut self, name: &str, v: &mut u16) {
+        if let Some(i) = self.int(name) {
+            *v = i as _;
+        }
+    }
+
+    fn visit_u32(&mut self, name: &str, v: &mut u32) {
+        if let Some(i) = self.int(name) {
+            *v = i as _;
+        }
+    }
+
+    fn visit_i8(&mut self, name: &str, v: &mut i8) {
+        if let Some(i) = self.int(name) {
+            *v = i as _;
+        }
+    }
+
+    fn visit_i16(&mut self, name: &str, v: &mut i16) {
+        if let Some(i) = self.int(name) {
+            *v = i as _;
+        }
+    }
+
+    fn visit_i32(&mut self, name: &str, v: &mut i32) {
+        if let Some(i) = self.int(name) {
+            *v = i as _;
+        }
+    }
+
+    fn visit_f32(&mut self, name: &str, v: &mut f32) {
+        if let Some(f) = self.0.get(name).and_then(FieldValue::as_f32) {
+            *v = f;
+        }
+    }
+
+    fn visit_bool(&mut self, name: &str, v: &mut bool) {
+        if let Some(b) = self.0.get(name).and_then(FieldValue::as_bool) {
+            *v = b;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::patch::{EditOp, FieldEdit, Literal, PatchApplier};
+
+    #[test]
+    fn test_capture_and_restore() {
+        let mut row: EquipParamWeapon = unsafe { std::mem::zeroed() };
+        row.weight = 2.0;
+        row.sort_id = 10;
+        row.icon_id = 7;
+
+        let mut values = HashMap::new();
+        row.visit_ref(&mut FieldCapture { fields: vec!["weight", "sort_id"], values: &mut values });
+        assert_eq!(values.len(), 2);
+        assert_eq!(values["weight"], FieldValue::F32(2.0));
+        assert_eq!(values["sort_id"], FieldValue::I32(10));
+
+        let edits = [
+            FieldEdit { field: "weight".into(), op: EditOp::Mul(Literal::Float(1.5)) },
+            FieldEdit { field: "sort_id".into(), op: EditOp::Set(Literal::Int(3)) },
+        ];
+        row.visit(&mut PatchApplier::new(&edits));
+        assert_eq!(row.weight, 3.0);
+
+        // Values that were already captured are kept.
+        row.visit_ref(&mut FieldCapture { fields: vec!["weight"], values: &mut values });
+        assert_eq!(values["weight"], FieldValue::F32(2.0));
+
+        row.icon_id = 8;
+        row.visit(&mut FieldRestore(&values));
+        assert_eq!(row.weight, 2.0);
+        assert_eq!(row.sort_id, 10);
+        assert_eq!(row.icon_id, 8);
+    }
+}
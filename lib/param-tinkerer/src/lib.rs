@@ -1,3 +1,5 @@
+mod search;
+
 use std::fmt::Write;
 
 use hudhook::hooks::dx11::ImguiDx11Hooks;
@@ -5,11 +7,18 @@ use hudhook::ImguiRenderLoop;
 use imgui::*;
 use libds3::prelude::*;
 
+use crate::search::{string_match, FieldQuery};
+
 struct ParamTinkerer {
     pointers: PointerChains,
     shown: bool,
     selected_param: usize,
     selected_param_id: usize,
+    param_filter: String,
+    row_filter: String,
+    query_field: String,
+    query_value: String,
+    query_results: Option<Vec<usize>>,
 }
 
 impl ParamTinkerer {
@@ -21,6 +30,11 @@ impl ParamTinkerer {
             shown: false,
             selected_param: 0,
             selected_param_id: 0,
+            param_filter: String::new(),
+            row_filter: String::new(),
+            query_field: String::new(),
+            query_value: String::new(),
+            query_results: None,
             pointers: PointerChains::new(),
         }
     }
@@ -79,11 +93,20 @@ impl ParamTinkerer {
                     ui.set_current_column_width(COLUMN1 + 10.);
 
                     let _ = ui.push_item_width(-1.);
-                    ListBox::new("##param_names").size([COLUMN1, 400.]).build(ui, || {
+                    ui.input_text("##param_filter", &mut self.param_filter)
+                        .hint("Filter params...")
+                        .build();
+                    ListBox::new("##param_names").size([COLUMN1, 376.]).build(ui, || {
                         for (idx, k) in params.keys().enumerate() {
+                            if !string_match(&self.param_filter, k) {
+                                continue;
+                            }
+
                             if ui.selectable_config(k).selected(idx == self.selected_param).build()
                             {
                                 self.selected_param = idx;
+                                self.selected_param_id = 0;
+                                self.query_results = None;
                             }
                         }
                     });
@@ -98,10 +121,18 @@ impl ParamTinkerer {
                     ui.next_column();
                     ui.set_current_column_width(COLUMN2 + 10.);
 
+                    self.render_query(ui, &params, param_name);
+
                     let mut buf = String::new();
                     let _ = ui.push_item_width(-1.);
-                    ListBox::new("##param_ids").size([COLUMN2, 400.]).build(ui, || {
+                    ListBox::new("##param_ids").size([COLUMN2, 352.]).build(ui, || {
                         for (idx, id) in param_entries.enumerate() {
+                            if let Some(results) = &self.query_results {
+                                if results.binary_search(&idx).is_err() {
+                                    continue;
+                                }
+                            }
+
                             buf.clear();
                             if let Some(id_name) = PARAM_NAMES
                                 .get(param_name)
@@ -112,6 +143,10 @@ impl ParamTinkerer {
                                 write!(buf, "{id}").ok();
                             }
 
+                            if !string_match(&self.row_filter, &buf) {
+                                continue;
+                            }
+
                             if ui
                                 .selectable_config(&buf)
                                 .selected(idx == self.selected_param_id)
@@ -126,51 +161,122 @@ impl ParamTinkerer {
                 });
 
                 if let Some((param_name, param_idx)) = param_item {
-                    struct ImguiParamVisitor<'a>(&'a imgui::Ui);
+                    struct ImguiParamVisitor<'a> {
+                        ui: &'a imgui::Ui,
+                        meta: Option<(String, FieldMeta)>,
+                    }
+
+                    impl ImguiParamVisitor<'_> {
+                        fn meta(&self, name: &str) -> Option<&FieldMeta> {
+                            self.meta.as_ref().filter(|(n, _)| n == name).map(|(_, meta)| meta)
+                        }
+
+                        /// Shows a combo box for enum fields whose value is
+                        /// known, and an input box otherwise.
+                        fn input_int(&self, name: &str, i: &mut i32) {
+                            let enum_values = self.meta(name).and_then(FieldMeta::enum_values);
+
+                            match enum_values.and_then(|values| {
+                                let idx = values.iter().position(|&(v, _)| v == *i as i64)?;
+                                Some((values, idx))
+                            }) {
+                                Some((values, mut idx)) => {
+                                    if self.ui.combo(name, &mut idx, values, |&(v, n)| {
+                                        format!("{v} - {n}").into()
+                                    }) {
+                                        *i = values[idx].0 as _;
+                                    }
+                                },
+                                None => {
+                                    self.ui.input_int(name, i).build();
+                                },
+                            }
+
+                            self.tooltip(name);
+                        }
+
+                        fn tooltip(&self, name: &str) {
+                            let Some(meta) = self.meta(name) else {
+                                return;
+                            };
+
+                            if !self.ui.is_item_hovered() {
+                                return;
+                            }
+
+                            self.ui.tooltip(|| {
+                                if let Some(display_name) = meta.display_name {
+                                    self.ui.text(display_name);
+                                }
+                                if let Some(description) = meta.description {
+                                    self.ui.text(description);
+                                }
+                                match (meta.min, meta.max) {
+                                    (Some(min), Some(max)) => {
+                                        self.ui.text(format!("Range: {min} to {max}"))
+                                    },
+                                    (Some(min), None) => self.ui.text(format!("Min: {min}")),
+                                    (None, Some(max)) => self.ui.text(format!("Max: {max}")),
+                                    (None, None) => {},
+                                }
+                            });
+                        }
+                    }
 
                     impl<'a> ParamVisitor for ImguiParamVisitor<'a> {
+                        fn visit_meta(&mut self, name: &str, meta: &FieldMeta) {
+                            self.meta = Some((name.to_string(), *meta));
+                        }
+
                         fn visit_u8(&mut self, name: &str, v: &mut u8) {
                             let mut i = *v as i32;
-                            self.0.input_int(name, &mut i).build();
+                            self.input_int(name, &mut i);
                             *v = i as _;
                         }
 
                         fn visit_u16(&mut self, name: &str, v: &mut u16) {
                             let mut i = *v as i32;
-                            self.0.input_int(name, &mut i).build();
+                            self.input_int(name, &mut i);
                             *v = i as _;
                         }
 
                         fn visit_u32(&mut self, name: &str, v: &mut u32) {
                             let mut i = *v as i32;
-                            self.0.input_int(name, &mut i).build();
+                            self.input_int(name, &mut i);
                             *v = i as _;
                         }
 
                         fn visit_i8(&mut self, name: &str, v: &mut i8) {
                             let mut i = *v as i32;
-                            self.0.input_int(name, &mut i).build();
+                            self.input_int(name, &mut i);
                             *v = i as _;
                         }
 
                         fn visit_i16(&mut self, name: &str, v: &mut i16) {
                             let mut i = *v as i32;
-                            self.0.input_int(name, &mut i).build();
+                            self.input_int(name, &mut i);
                             *v = i as _;
                         }
 
                         fn visit_i32(&mut self, name: &str, v: &mut i32) {
-                            let mut i = *v;
-                            self.0.input_int(name, &mut i).build();
-                            *v = i as _;
+                            self.input_int(name, v);
                         }
 
                         fn visit_f32(&mut self, name: &str, v: &mut f32) {
-                            self.0.input_float(name, v).build();
+                            self.ui.input_float(name, v).build();
+                            self.tooltip(name);
                         }
 
                         fn visit_bool(&mut self, name: &str, v: &mut bool) {
-                            self.0.checkbox(name, v);
+                            self.ui.checkbox(name, v);
+                            self.tooltip(name);
+                        }
+
+                        fn visit_bit(&mut self, name: &str, field: &str, bit: u8, v: &mut bool) {
+                            self.ui.checkbox(name, v);
+                            if self.ui.is_item_hovered() {
+                                self.ui.tooltip_text(format!("{field}, bit {bit}"));
+                            }
                         }
                     }
 
@@ -178,11 +284,52 @@ impl ParamTinkerer {
 
                     ListBox::new("##param_detail").size([COLUMN3, 400.]).build(ui, || {
                         let _token = ui.push_item_width(120.);
-                        params.visit_param_item(param_name, param_idx, &mut ImguiParamVisitor(ui));
+                        params.visit_param_item(param_name, param_idx, &mut ImguiParamVisitor {
+                            ui,
+                            meta: None,
+                        });
                     });
                 };
             });
     }
+
+    fn render_query(&mut self, ui: &imgui::Ui, params: &Params, param_name: &str) {
+        const QUERY_WIDTH: f32 = 180.;
+
+        ui.set_next_item_width(-1.);
+        ui.input_text("##row_filter", &mut self.row_filter).hint("Filter rows...").build();
+
+        {
+            let _token = ui.push_item_width(QUERY_WIDTH);
+            ui.input_text("##query_field", &mut self.query_field).hint("Field").build();
+            ui.same_line();
+            ui.input_text("##query_value", &mut self.query_value).hint("Value").build();
+        }
+
+        ui.same_line();
+        if ui.button("Find") {
+            self.query_results = FieldQuery::new(&self.query_field, &self.query_value).map(|q| {
+                let count = unsafe { params.iter_param_ids(param_name) }
+                    .map(|ids| ids.count())
+                    .unwrap_or(0);
+
+                (0..count)
+                    .filter(|&idx| {
+                        let mut matcher = q.matcher();
+                        params.visit_param_item_ref(param_name, idx, &mut matcher);
+                        matcher.matched()
+                    })
+                    .collect()
+            });
+        }
+
+        ui.same_line();
+        if ui.button("Reset") {
+            self.query_field.clear();
+            self.query_value.clear();
+            self.query_results = None;
+        }
+    }
 }
 
 hudhook::hudhook!(ImguiDx11Hooks, ParamTinkerer::new());
@@ -0,0 +1,166 @@
+use libds3::prelude::*;
+
+/// Case-insensitive substring match. An empty needle matches everything.
+pub(crate) fn string_match(needle: &str, haystack: &str) -> bool {
+    needle.is_empty() || haystack.to_lowercase().contains(&needle.to_lowercase())
+}
+
+/// A "find rows where `field` == `value`" query.
+#[derive(Debug, Clone)]
+pub(crate) struct FieldQuery {
+    field: String,
+    value: String,
+}
+
+impl FieldQuery {
+    /// Returns `None` if either the field name or the value are empty.
+    pub(crate) fn new(field: &str, value: &str) -> Option<Self> {
+        let field = field.trim();
+        let value = value.trim();
+
+        if field.is_empty() || value.is_empty() {
+            None
+        } else {
+            Some(FieldQuery { field: field.to_string(), value: value.to_string() })
+        }
+    }
+
+    pub(crate) fn matcher(&self) -> FieldQueryMatcher<'_> {
+        FieldQueryMatcher { query: self, matched: false }
+    }
+
+    fn matches(&self, name: &str, value: FieldValue) -> bool {
+        if name != self.field {
+            return false;
+        }
+
+        match value {
+            FieldValue::F32(v) => {
+                self.value.parse::<f32>().map(|q| (q - v).abs() <= f32::EPSILON).unwrap_or(false)
+            },
+            FieldValue::Bool(v) => match self.value.to_lowercase().as_str() {
+                "true" | "1" => v,
+                "false" | "0" => !v,
+                _ => false,
+            },
+            v => self.value.parse::<i64>().ok() == v.as_i64(),
+        }
+    }
+}
+
+/// Visits a param row and records whether any field satisfies the query.
+pub(crate) struct FieldQueryMatcher<'a> {
+    query: &'a FieldQuery,
+    matched: bool,
+}
+
+impl FieldQueryMatcher<'_> {
+    pub(crate) fn matched(&self) -> bool {
+        self.matched
+    }
+}
+
+impl ParamRefVisitor for FieldQueryMatcher<'_> {
+    fn visit_field(&mut self, name: &'static str, value: FieldValue) {
+        self.matched |= self.query.matches(name, value);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_string_match() {
+        assert!(string_match("", "anything"));
+        assert!(string_match("weapon", "EquipParamWeapon"));
+        assert!(string_match("1000", "2001000 - Dagger"));
+        assert!(string_match("dag", "2001000 - Dagger"));
+        assert!(!string_match("sword", "2001000 - Dagger"));
+    }
+
+    #[test]
+    fn test_empty_query() {
+        assert!(FieldQuery::new("", "1").is_none());
+        assert!(FieldQuery::new("weight", "  ").is_none());
+    }
+
+    #[test]
+    fn test_query_int() {
+        let query = FieldQuery::new("sort_id", "120").unwrap();
+
+        let mut m = query.matcher();
+        m.visit_field("sort_id", FieldValue::I32(120));
+        assert!(m.matched());
+
+        let mut m = query.matcher();
+        m.visit_field("sort_id", FieldValue::I32(121));
+        m.visit_field("icon_id", FieldValue::U16(120));
+        assert!(!m.matched());
+
+        let query = FieldQuery::new("atk_attribute", "-1").unwrap();
+        let mut m = query.matcher();
+        m.visit_field("atk_attribute", FieldValue::I8(-1));
+        assert!(m.matched());
+    }
+
+    #[test]
+    fn test_query_float() {
+        let query = FieldQuery::new("weight", "1.5").unwrap();
+
+        let mut m = query.matcher();
+        m.visit_field("weight", FieldValue::F32(1.5));
+        assert!(m.matched());
+
+        let mut m = query.matcher();
+        m.visit_field("weight", FieldValue::F32(2.0));
+        assert!(!m.matched());
+    }
+
+    #[test]
+    fn test_query_bool() {
+        let query = FieldQuery::new("is_deposit", "true").unwrap();
+
+        let mut m = query.matcher();
+        m.visit_field("is_deposit", FieldValue::Bool(true));
+        assert!(m.matched());
+
+        let query = FieldQuery::new("is_deposit", "0").unwrap();
+        let mut m = query.matcher();
+        m.visit_field("is_deposit", FieldValue::Bool(false));
+        assert!(m.matched());
+
+        let mut m = query.matcher();
+        m.visit_field("is_deposit", FieldValue::Bool(true));
+        assert!(!m.matched());
+    }
+
+    #[test]
+    fn test_query_row() {
+        let mut row: EquipParamWeapon = unsafe { std::mem::zeroed() };
+        row.sort_id = 120;
+        row.weight = 1.5;
+
+        let query = FieldQuery::new("sort_id", "120").unwrap();
+        let mut m = query.matcher();
+        row.visit_ref(&mut m);
+        assert!(m.matched());
+
+        let query = FieldQuery::new("weight", "2.5").unwrap();
+        let mut m = query.matcher();
+        row.visit_ref(&mut m);
+        assert!(!m.matched());
+        assert!(row
+            .fields()
+            .any(|(name, value)| name == "weight" && value == FieldValue::F32(1.5)));
+    }
+
+    #[test]
+    fn test_query_type_mismatch() {
+        let query = FieldQuery::new("sort_id", "abc").unwrap();
+
+        let mut m = query.matcher();
+        m.visit_field("sort_id", FieldValue::I32(0));
+        assert!(!m.matched());
+    }
+}
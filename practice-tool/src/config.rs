@@ -6,18 +6,30 @@ use practice_tool_core::widgets::Widget;
 use serde::Deserialize;
 use tracing_subscriber::filter::LevelFilter;
 
-use crate::widgets::character_stats::character_stats_edit;
+use crate::widgets::bonfires::BonfireManager;
+use crate::widgets::camera::{CameraPreset, CameraTweaks};
+use crate::widgets::character_stats::{
+    character_stats_edit, stats_preset, CharacterChains, Class, StatsPreset,
+};
+use crate::widgets::covenant::Covenant;
 use crate::widgets::cycle_speed::cycle_speed;
+use crate::widgets::equipment::{
+    EquipLoadout, EquipmentLoadouts, EquipmentManager, Equipper, EQUIPMENT_FILE,
+};
 use crate::widgets::flag::flag_widget;
+use crate::widgets::free_camera::{FreeCamera, MovementKeys};
 use crate::widgets::group::group;
+use crate::widgets::inventory::InventoryEditor;
+use crate::widgets::item_loadouts::{Loadouts, LOADOUTS_FILE};
 use crate::widgets::item_spawn::ItemSpawner;
-use crate::widgets::nudge_pos::nudge_position;
+use crate::widgets::nudge_pos::{nudge_position, NudgeFacing};
 use crate::widgets::open_menu::{open_menu, OpenMenuKind};
 use crate::widgets::position::save_position;
 use crate::widgets::quitout::quitout;
 use crate::widgets::savefile_manager::savefile_manager;
 use crate::widgets::souls::souls;
 use crate::widgets::target::Target;
+use crate::widgets::warp::{BonfireWarpMenu, WarpToBonfire};
 
 #[derive(Debug, Deserialize)]
 pub(crate) struct Config {
@@ -41,6 +53,12 @@ pub(crate) struct Settings {
 pub(crate) enum Indicator {
     Igt,
     Position,
+    Speed,
+    /// Distance to the position saved by a slot, by index in the order of
+    /// the configuration: `saved_position_distance` is the first slot, and
+    /// `saved_position_distance:2` the second one.
+    SavedPositionDistance(usize),
+    FallHeight,
     GameVersion,
     ImguiDebug,
 }
@@ -58,9 +76,17 @@ impl TryFrom<String> for Indicator {
         match value.as_str() {
             "igt" => Ok(Indicator::Igt),
             "position" => Ok(Indicator::Position),
+            "speed" => Ok(Indicator::Speed),
+            "saved_position_distance" => Ok(Indicator::SavedPositionDistance(0)),
+            "fall_height" => Ok(Indicator::FallHeight),
             "game_version" => Ok(Indicator::GameVersion),
             "imgui_debug" => Ok(Indicator::ImguiDebug),
-            value => Err(format!("Unrecognized indicator: {value}")),
+            value => value
+                .strip_prefix("saved_position_distance:")
+                .and_then(|slot| slot.parse::<usize>().ok())
+                .filter(|&slot| slot > 0)
+                .map(|slot| Indicator::SavedPositionDistance(slot - 1))
+                .ok_or_else(|| format!("Unrecognized indicator: {value}")),
         }
     }
 }
@@ -93,6 +119,47 @@ enum CfgCommand {
         #[serde(rename = "item_spawner")]
         hotkey_load: PlaceholderOption<Key>,
     },
+    Inventory {
+        #[serde(rename = "inventory")]
+        hotkey_open: PlaceholderOption<Key>,
+    },
+    Equipment {
+        #[serde(rename = "equipment")]
+        hotkey_open: PlaceholderOption<Key>,
+    },
+    EquipLoadout {
+        #[serde(rename = "equip")]
+        name: String,
+        hotkey: Option<Key>,
+    },
+    BonfireWarp {
+        #[serde(rename = "bonfire_warp")]
+        hotkey_open: PlaceholderOption<Key>,
+    },
+    Bonfires {
+        #[serde(rename = "bonfires")]
+        hotkey_open: PlaceholderOption<Key>,
+    },
+    WarpToBonfire {
+        #[serde(rename = "warp")]
+        id: u64,
+        hotkey: Option<Key>,
+    },
+    FreeCamera {
+        #[serde(rename = "free_camera")]
+        hotkey: PlaceholderOption<Key>,
+        #[serde(default = "CfgCommand::default_free_camera_speed")]
+        speed: f32,
+        #[serde(default)]
+        keys: MovementKeys,
+        teleport: Option<Key>,
+    },
+    Camera {
+        #[serde(rename = "camera")]
+        hotkey: PlaceholderOption<Key>,
+        #[serde(default)]
+        presets: Vec<CameraPreset>,
+    },
     Flag {
         flag: FlagSpec,
         hotkey: Option<Key>,
@@ -109,6 +176,19 @@ enum CfgCommand {
     CharacterStats {
         #[serde(rename = "character_stats")]
         value: PlaceholderOption<Key>,
+        class: Option<Class>,
+    },
+    Covenant {
+        #[serde(rename = "covenant")]
+        enabled: bool,
+    },
+    StatsPreset {
+        #[serde(rename = "stats_preset")]
+        name: String,
+        class: Option<Class>,
+        #[serde(default)]
+        stats: StatsPreset,
+        hotkey: Option<Key>,
     },
     Souls {
         #[serde(rename = "souls")]
@@ -133,6 +213,16 @@ enum CfgCommand {
         nudge_up: Option<Key>,
         nudge_down: Option<Key>,
     },
+    NudgeFacing {
+        #[serde(rename = "nudge_facing")]
+        step_forward: f32,
+        /// Defaults to the forward step.
+        nudge_sideways: Option<f32>,
+        forward: Option<Key>,
+        back: Option<Key>,
+        left: Option<Key>,
+        right: Option<Key>,
+    },
     Group {
         #[serde(rename = "group")]
         label: String,
@@ -140,7 +230,24 @@ enum CfgCommand {
     },
 }
 
+fn equipper(chains: &PointerChains) -> Equipper {
+    Equipper::new(
+        chains.inventory.clone(),
+        chains.spawn_item_func_ptr as usize,
+        chains.map_item_man as usize,
+    )
+}
+
 impl CfgCommand {
+    fn default_free_camera_speed() -> f32 {
+        10.
+    }
+
+    /// Whether the command builds a widget: `{ covenant = false }` doesn't.
+    fn enabled(&self) -> bool {
+        !matches!(self, CfgCommand::Covenant { enabled: false })
+    }
+
     fn into_widget(self, settings: &Settings, chains: &PointerChains) -> Box<dyn Widget> {
         match self {
             CfgCommand::Flag { flag, hotkey: key } => {
@@ -155,18 +262,83 @@ impl CfgCommand {
                 chains.gravity.clone(),
                 key_load.into_option(),
                 settings.display,
+                Loadouts::load_default(LOADOUTS_FILE),
+            )),
+            CfgCommand::Inventory { hotkey_open } => Box::new(InventoryEditor::new(
+                chains.inventory.clone(),
+                chains.spawn_item_func_ptr as usize,
+                chains.map_item_man as usize,
+                hotkey_open.into_option(),
+                settings.display,
             )),
-            CfgCommand::Position { position, save } => {
-                save_position(chains.position.clone(), position.into_option(), save)
+            CfgCommand::Equipment { hotkey_open } => Box::new(EquipmentManager::new(
+                equipper(chains),
+                EquipmentLoadouts::load_default(LOADOUTS_FILE),
+                hotkey_open.into_option(),
+                settings.display,
+            )),
+            CfgCommand::EquipLoadout { name, hotkey } => {
+                Box::new(EquipLoadout::new(equipper(chains), name, hotkey))
+            },
+            CfgCommand::BonfireWarp { hotkey_open } => {
+                Box::new(BonfireWarpMenu::new(hotkey_open.into_option(), settings.display))
             },
-            CfgCommand::NudgePosition { nudge, nudge_up, nudge_down } => {
-                nudge_position(chains.position.clone(), nudge, nudge_up, nudge_down)
+            CfgCommand::Bonfires { hotkey_open } => {
+                Box::new(BonfireManager::new(hotkey_open.into_option(), settings.display))
+            },
+            CfgCommand::WarpToBonfire { id, hotkey } => Box::new(WarpToBonfire::new(id, hotkey)),
+            CfgCommand::FreeCamera { hotkey, speed, keys, teleport } => Box::new(FreeCamera::new(
+                chains.position.1.clone(),
+                speed,
+                keys,
+                hotkey.into_option(),
+                teleport,
+            )),
+            CfgCommand::Camera { hotkey, presets } => {
+                Box::new(CameraTweaks::new(presets, hotkey.into_option()))
             },
-            CfgCommand::CharacterStats { value } => character_stats_edit(
-                chains.character_stats.clone(),
+            CfgCommand::Position { position, save } => save_position(
+                chains.position.clone(),
+                chains.map_id.clone(),
+                position.into_option(),
+                save,
+            ),
+            CfgCommand::NudgePosition { nudge, nudge_up, nudge_down } => nudge_position(
+                chains.position.clone(),
+                chains.map_id.clone(),
+                nudge,
+                nudge_up,
+                nudge_down,
+            ),
+            CfgCommand::NudgeFacing {
+                step_forward,
+                nudge_sideways,
+                forward,
+                back,
+                left,
+                right,
+            } => Box::new(NudgeFacing::new(
+                chains.position.clone(),
+                step_forward,
+                nudge_sideways.unwrap_or(step_forward),
+                [forward, back, left, right],
+            )),
+            CfgCommand::CharacterStats { value, class } => character_stats_edit(
+                CharacterChains {
+                    stats: chains.character_stats.clone(),
+                    ng_cycle: chains.ng_cycle.clone(),
+                    hollowing: chains.hollowing.clone(),
+                },
+                class,
                 value.into_option(),
                 settings.display,
             ),
+            CfgCommand::Covenant { .. } => {
+                Box::new(Covenant::new(chains.covenant.clone(), chains.inventory.clone()))
+            },
+            CfgCommand::StatsPreset { name, class, stats, hotkey } => {
+                stats_preset(&name, chains.character_stats.clone(), class, stats, hotkey)
+            },
             CfgCommand::CycleSpeed { values, hotkey } => {
                 cycle_speed(values.as_slice(), chains.speed.clone(), hotkey)
             },
@@ -182,7 +354,11 @@ impl CfgCommand {
             )),
             CfgCommand::Group { label, commands } => group(
                 label.as_str(),
-                commands.into_iter().map(|c| c.into_widget(settings, chains)).collect(),
+                commands
+                    .into_iter()
+                    .filter(CfgCommand::enabled)
+                    .map(|c| c.into_widget(settings, chains))
+                    .collect(),
                 settings.display,
             ),
         }
@@ -216,7 +392,11 @@ impl Config {
     }
 
     pub(crate) fn make_commands(self, chains: &PointerChains) -> Vec<Box<dyn Widget>> {
-        self.commands.into_iter().map(|c| c.into_widget(&self.settings, chains)).collect()
+        self.commands
+            .into_iter()
+            .filter(CfgCommand::enabled)
+            .map(|c| c.into_widget(&self.settings, chains))
+            .collect()
     }
 }
 
@@ -300,6 +480,20 @@ mod tests {
         println!("{:#?}", Config::parse(include_str!("../../jdsd_dsiii_practice_tool.toml")));
     }
 
+    #[test]
+    fn test_disabled_commands() {
+        let config = Config::parse(
+            r#"commands = [ { covenant = false }, { covenant = true } ]
+            [settings]
+            log_level = "DEBUG"
+            display = "0"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(config.commands.iter().filter(|c| c.enabled()).count(), 1);
+    }
+
     #[test]
     fn test_parse_errors() {
         println!(
@@ -15,6 +15,7 @@
 // along with this program.  If not, see <https://www.gnu.org/licenses/>.
 
 mod config;
+mod motion;
 mod practice_tool;
 mod util;
 mod widgets;
@@ -0,0 +1,208 @@
+//! Quantities derived from consecutive reads of the player's position:
+//! speed, and height changes for fall damage practice.
+//!
+//! The position is read every frame, but the game doesn't necessarily update
+//! it at the same rate, so speeds are computed over [`SAMPLE_INTERVAL`]
+//! rather than between single frames.
+//!
+//! The player is on the ground when the vertical speed is steady and either
+//! low or matching a walkable slope. A landing takes [`GROUND_SAMPLES`] such
+//! samples in a row, so that the apex of a jump, where the vertical speed
+//! crosses zero under gravity, isn't one.
+
+/// Minimum time, in seconds, over which speeds are computed.
+const SAMPLE_INTERVAL: f32 = 0.1;
+/// Samples further apart than this, in seconds, restart the measurements,
+/// e.g. after loading screens or while the tool is hidden.
+const MAX_GAP: f32 = 1.;
+/// Vertical speed, in units per second, under which the player may be on
+/// the ground.
+const GROUND_SPEED: f32 = 0.5;
+/// Steepest walkable slope, as vertical over horizontal speed.
+const MAX_SLOPE: f32 = 1.;
+/// Vertical acceleration, in units per second squared, over which the
+/// player is airborne: gravity, jumps and landings are all above it.
+const MAX_GROUND_ACCELERATION: f32 = 5.;
+/// Ground samples in a row needed for a landing.
+const GROUND_SAMPLES: u32 = 3;
+
+#[derive(Debug, Default)]
+pub(crate) struct Motion {
+    /// Position and elapsed time since the last speed computation.
+    anchor: Option<([f32; 3], f32)>,
+    horizontal_speed: f32,
+    vertical_speed: f32,
+    /// Ground samples in a row so far.
+    ground_samples: u32,
+    /// Height of the last ground contact.
+    ground: Option<f32>,
+    /// Highest point reached since the last ground contact.
+    peak: Option<f32>,
+    last_fall: f32,
+}
+
+impl Motion {
+    /// Feeds the position read `dt` seconds after the previous one.
+    pub(crate) fn push(&mut self, position: [f32; 3], dt: f32) {
+        if dt > MAX_GAP {
+            *self = Motion { last_fall: self.last_fall, ..Default::default() };
+        }
+
+        let Some((anchor, elapsed)) = self.anchor.as_mut() else {
+            self.anchor = Some((position, 0.));
+            self.ground = Some(position[1]);
+            return;
+        };
+
+        *elapsed += dt;
+        if *elapsed < SAMPLE_INTERVAL {
+            return;
+        }
+
+        let [dx, dy, dz] = std::array::from_fn(|i| position[i] - anchor[i]);
+        let vertical_speed = dy / *elapsed;
+        let acceleration = (vertical_speed - self.vertical_speed) / *elapsed;
+        self.horizontal_speed = (dx * dx + dz * dz).sqrt() / *elapsed;
+        self.vertical_speed = vertical_speed;
+        self.anchor = Some((position, 0.));
+
+        let on_ground = acceleration.abs() < MAX_GROUND_ACCELERATION
+            && (vertical_speed.abs() < GROUND_SPEED
+                || vertical_speed.abs() <= MAX_SLOPE * self.horizontal_speed);
+        self.ground_samples = if on_ground { self.ground_samples + 1 } else { 0 };
+
+        let y = position[1];
+        if self.ground_samples >= GROUND_SAMPLES {
+            if let Some(peak) = self.peak.take() {
+                self.last_fall = peak - y;
+            }
+            self.ground = Some(y);
+        } else if !on_ground {
+            let ground = self.ground.unwrap_or(y);
+            self.peak = Some(self.peak.unwrap_or(ground).max(y));
+        }
+    }
+
+    /// Units per second on the horizontal plane.
+    pub(crate) fn horizontal_speed(&self) -> f32 {
+        self.horizontal_speed
+    }
+
+    /// Units per second, positive upwards.
+    pub(crate) fn vertical_speed(&self) -> f32 {
+        self.vertical_speed
+    }
+
+    /// Height relative to the last ground contact.
+    pub(crate) fn height_change(&self) -> f32 {
+        match (self.anchor, self.ground) {
+            (Some(([_, y, _], _)), Some(ground)) => y - ground,
+            _ => 0.,
+        }
+    }
+
+    /// Drop from the highest point of the last completed fall to where it
+    /// landed.
+    pub(crate) fn last_fall(&self) -> f32 {
+        self.last_fall
+    }
+}
+
+/// Straight-line distance between two positions.
+pub(crate) fn distance([x0, y0, z0]: [f32; 3], [x1, y1, z1]: [f32; 3]) -> f32 {
+    ((x1 - x0).powi(2) + (y1 - y0).powi(2) + (z1 - z0).powi(2)).sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(a: f32, b: f32) {
+        assert!((a - b).abs() < 1e-3, "{a} != {b}");
+    }
+
+    #[test]
+    fn test_speed() {
+        let mut motion = Motion::default();
+        motion.push([0., 0., 0.], 0.);
+        motion.push([0.3, 0., 0.4], 0.05);
+        // Not enough time has passed yet.
+        assert_close(motion.horizontal_speed(), 0.);
+
+        motion.push([0.6, -0.1, 0.8], 0.05);
+        assert_close(motion.horizontal_speed(), 10.);
+        assert_close(motion.vertical_speed(), -1.);
+    }
+
+    #[test]
+    fn test_fall() {
+        let mut motion = Motion::default();
+        motion.push([0., 10., 0.], 0.);
+        motion.push([0., 10., 0.], 0.1);
+
+        // Jump up by 1, then fall 6.
+        for y in [10.5, 11., 9., 7., 5.] {
+            motion.push([0., y, 0.], 0.1);
+        }
+        assert_close(motion.height_change(), -5.);
+        assert_close(motion.last_fall(), 0.);
+
+        // Landing takes a few steady samples.
+        motion.push([0., 5., 0.], 0.1);
+        assert_close(motion.last_fall(), 0.);
+        for _ in 0..GROUND_SAMPLES {
+            motion.push([0., 5., 0.], 0.1);
+        }
+        assert_close(motion.height_change(), 0.);
+        assert_close(motion.last_fall(), 6.);
+
+        // A gap restarts the measurements but keeps the last fall.
+        motion.push([0., 50., 0.], 2.);
+        assert_close(motion.height_change(), 0.);
+        assert_close(motion.horizontal_speed(), 0.);
+        assert_close(motion.last_fall(), 6.);
+    }
+
+    #[test]
+    fn test_jump_arc() {
+        let mut motion = Motion::default();
+        motion.push([0., 0., 0.], 0.);
+        for _ in 0..GROUND_SAMPLES {
+            motion.push([0., 0., 0.], 0.1);
+        }
+
+        // Jump at 5 units per second, with a gravity of 20: the vertical
+        // speed is zero at the apex, which isn't a landing.
+        for y in [0.4, 0.6, 0.6, 0.4] {
+            motion.push([0., y, 0.], 0.1);
+        }
+        assert_close(motion.height_change(), 0.4);
+        assert_close(motion.last_fall(), 0.);
+
+        // The impact isn't steady, nor is the sample right after it.
+        for _ in 0..GROUND_SAMPLES + 2 {
+            motion.push([0., 0., 0.], 0.1);
+        }
+        assert_close(motion.height_change(), 0.);
+        assert_close(motion.last_fall(), 0.6);
+    }
+
+    #[test]
+    fn test_slope() {
+        let mut motion = Motion::default();
+        motion.push([0., 10., 0.], 0.);
+
+        // Walking down a slope at 3 units per second horizontally.
+        for i in 1..=10 {
+            let i = i as f32;
+            motion.push([0.3 * i, 10. - 0.15 * i, 0.], 0.1);
+        }
+        assert_close(motion.vertical_speed(), -1.5);
+        assert_close(motion.height_change(), 0.);
+    }
+
+    #[test]
+    fn test_distance() {
+        assert_close(distance([1., 2., 3.], [4., 6., 3.]), 5.);
+    }
+}
@@ -1,6 +1,6 @@
 use std::fmt::Write;
 use std::sync::Mutex;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 use const_format::formatcp;
 use hudhook::tracing::metadata::LevelFilter;
@@ -14,12 +14,16 @@ use practice_tool_core::widgets::{scaling_factor, Widget, BUTTON_HEIGHT, BUTTON_
 use tracing_subscriber::prelude::*;
 
 use crate::config::{Config, Indicator, Settings};
+use crate::motion::{self, Motion};
 use crate::util;
+use crate::widgets::position::SAVED_POSITIONS;
 
 const MAJOR: usize = pkg_version_major!();
 const MINOR: usize = pkg_version_minor!();
 const PATCH: usize = pkg_version_patch!();
 
+const PARAMS_CHECK_INTERVAL: Duration = Duration::from_secs(2);
+
 struct FontIDs {
     small: FontId,
     normal: FontId,
@@ -49,6 +53,10 @@ pub(crate) struct PracticeTool {
 
     position_bufs: [String; 4],
     igt_buf: String,
+    motion_buf: String,
+    params_check: Instant,
+    motion: Motion,
+    motion_sample: Instant,
 }
 
 impl PracticeTool {
@@ -136,18 +144,23 @@ impl PracticeTool {
         }
 
         {
+            // Reapplied whenever the game reloads its params.
+            fn patch_darksign_icon(params: &Params) {
+                if let Some(darksign) =
+                    unsafe { params.get_equip_param_goods_by_id(117) }.and_then(|p| p.param)
+                {
+                    darksign.icon_id = 116;
+                }
+            }
+
             let mut params = PARAMS.write();
-            if let Some(darksign) = wait_option(|| unsafe {
+            params.on_refresh(patch_darksign_icon);
+            drop(wait_option(|| unsafe {
                 if let Err(e) = params.refresh() {
                     error!("{}", e);
                 }
                 params.get_equip_param_goods()
-            })
-            .find(|i| i.id == 117)
-            .and_then(|p| p.param)
-            {
-                darksign.icon_id = 116;
-            }
+            }));
         }
 
         let pointers = PointerChains::new();
@@ -173,6 +186,10 @@ impl PracticeTool {
             ui_state: UiState::Closed,
             position_bufs: Default::default(),
             igt_buf: Default::default(),
+            motion_buf: Default::default(),
+            params_check: Instant::now(),
+            motion: Motion::default(),
+            motion_sample: Instant::now(),
         }
     }
 
@@ -327,6 +344,54 @@ impl PracticeTool {
                                 ui.text(&self.position_bufs[3]);
                             }
                         },
+                        Indicator::Speed => {
+                            self.motion_buf.clear();
+                            write!(
+                                self.motion_buf,
+                                "Speed H {:.2} V {:.2}",
+                                self.motion.horizontal_speed(),
+                                self.motion.vertical_speed()
+                            )
+                            .ok();
+                            ui.text(&self.motion_buf);
+                        },
+                        Indicator::SavedPositionDistance(slot) => {
+                            let saved = SAVED_POSITIONS
+                                .lock()
+                                .ok()
+                                .and_then(|saved| saved.get(slot).copied().flatten());
+                            let current_map = self.pointers.map_id.read().map(MapId);
+                            let position = self.pointers.position.1.read();
+
+                            // Distances across maps are meaningless, and so
+                            // are unknown maps.
+                            if let (Some(saved), Some(current_map), Some(position)) =
+                                (saved, current_map, position)
+                            {
+                                if saved.map.is_some_and(|map| map.same_block(&current_map)) {
+                                    self.motion_buf.clear();
+                                    write!(
+                                        self.motion_buf,
+                                        "Saved pos. {} {:.2}",
+                                        slot + 1,
+                                        motion::distance(position, saved.position)
+                                    )
+                                    .ok();
+                                    ui.text(&self.motion_buf);
+                                }
+                            }
+                        },
+                        Indicator::FallHeight => {
+                            self.motion_buf.clear();
+                            write!(
+                                self.motion_buf,
+                                "Height {:.2} Last fall {:.2}",
+                                self.motion.height_change(),
+                                self.motion.last_fall()
+                            )
+                            .ok();
+                            ui.text(&self.motion_buf);
+                        },
                         Indicator::Igt => {
                             if let Some(igt) = self.pointers.igt.read() {
                                 let millis = (igt % 1000) / 10;
@@ -363,12 +428,40 @@ impl PracticeTool {
         }
     }
 
+    fn sample_motion(&mut self) {
+        if let Some(position) = self.pointers.position.1.read() {
+            self.motion.push(position, self.motion_sample.elapsed().as_secs_f32());
+            self.motion_sample = Instant::now();
+        }
+    }
+
     fn render_hidden(&mut self, ui: &imgui::Ui) {
         for w in self.widgets.iter_mut() {
             w.interact(ui);
         }
     }
 
+    /// Refreshes the params if the game reloaded them since the last check.
+    fn check_params(&mut self) {
+        if self.params_check.elapsed() < PARAMS_CHECK_INTERVAL {
+            return;
+        }
+        self.params_check = Instant::now();
+
+        // Don't stall the frame if something else is editing the params.
+        let Some(mut params) = PARAMS.try_write() else {
+            return;
+        };
+
+        match unsafe { params.ensure_fresh() } {
+            Ok(true) => {
+                self.log_tx.send("Params reloaded by the game, refreshed".to_string()).ok();
+            },
+            Ok(false) => {},
+            Err(e) => error!("Couldn't check params: {}", e),
+        }
+    }
+
     fn render_logs(&mut self, ui: &imgui::Ui) {
         let io = ui.io();
 
@@ -464,6 +557,9 @@ impl ImguiRenderLoop for PracticeTool {
             },
         }
 
+        self.check_params();
+        self.sample_motion();
+
         for w in &mut self.widgets {
             w.log(self.log_tx.clone());
         }
@@ -0,0 +1,139 @@
+use libds3::prelude::*;
+use practice_tool_core::crossbeam_channel::Sender;
+use practice_tool_core::key::Key;
+use practice_tool_core::widgets::Widget;
+
+use crate::widgets::modal::{button_height, filter_input, Modal, HALF_WIDTH, MODAL_WIDTH};
+
+const BONFIRES_TAG: &str = "##bonfires";
+
+/// Lists the bonfires with their lit state, and lights or unlights them.
+///
+/// The list is [`BONFIRE_FLAGS`] rather than the rows of `BonfireWarpParam`
+/// used by the bonfire warp: the rows don't hold the flags, see
+/// [`libds3::bonfire_flags`].
+#[derive(Debug)]
+pub(crate) struct BonfireManager {
+    event_flags: Option<EventFlags>,
+    modal: Modal,
+
+    /// Lit state of each entry of [`BONFIRE_FLAGS`]. `None` when the flag
+    /// can't be read, e.g. when not in game.
+    lit: Vec<Option<bool>>,
+    filter_string: String,
+    logs: Vec<String>,
+}
+
+impl BonfireManager {
+    pub(crate) fn new(hotkey_open: Option<Key>, hotkey_close: Key) -> Self {
+        BonfireManager {
+            event_flags: EventFlags::new(),
+            modal: Modal::new("Bonfires", BONFIRES_TAG, hotkey_open, hotkey_close),
+            lit: Vec::new(),
+            filter_string: String::new(),
+            logs: Vec::new(),
+        }
+    }
+
+    fn refresh(&mut self) {
+        self.lit = BONFIRE_FLAGS
+            .iter()
+            .map(|bonfire| self.event_flags.and_then(|f| f.get(bonfire.flag)))
+            .collect();
+    }
+
+    fn set_lit(&mut self, idx: usize, lit: bool) {
+        let Some(event_flags) = self.event_flags else {
+            self.logs.push("Bonfire flags aren't supported on this game version".to_string());
+            return;
+        };
+
+        let bonfire = &BONFIRE_FLAGS[idx];
+        match event_flags.set(bonfire.flag, lit) {
+            Some(()) if lit => self.logs.push(format!("Lit {}", bonfire.name)),
+            Some(()) => self.logs.push(format!("Unlit {}", bonfire.name)),
+            None => self.logs.push(format!("Couldn't set {} flag", bonfire.name)),
+        }
+    }
+
+    fn light_all(&mut self) {
+        let Some(event_flags) = self.event_flags else {
+            self.logs.push("Bonfire flags aren't supported on this game version".to_string());
+            return;
+        };
+
+        let failed = BONFIRE_FLAGS
+            .iter()
+            .filter(|bonfire| event_flags.set(bonfire.flag, true).is_none())
+            .count();
+
+        match failed {
+            0 => self.logs.push("Lit every bonfire".to_string()),
+            n => self.logs.push(format!("Lit every bonfire, except {n} that couldn't be set")),
+        }
+        self.refresh();
+    }
+}
+
+impl Widget for BonfireManager {
+    fn render(&mut self, ui: &imgui::Ui) {
+        if self.modal.open_button(ui) {
+            self.refresh();
+        }
+
+        if let Some(_token) = self.modal.begin(ui) {
+            let button_height = button_height(ui);
+            let filter = filter_input(ui, "##bonfires-filter", &mut self.filter_string);
+            let mut toggled = None;
+
+            ui.child_window("##bonfires-list").size([MODAL_WIDTH, 200.]).build(|| {
+                for (idx, bonfire) in BONFIRE_FLAGS.iter().enumerate() {
+                    if !filter.is_empty()
+                        && !bonfire.name.to_lowercase().contains(&filter)
+                        && !bonfire.area.to_lowercase().contains(&filter)
+                    {
+                        continue;
+                    }
+
+                    let label = format!("{} ({})##{}", bonfire.name, bonfire.area, bonfire.flag);
+                    match self.lit.get(idx).copied().flatten() {
+                        Some(mut lit) => {
+                            if ui.checkbox(label, &mut lit) {
+                                toggled = Some((idx, lit));
+                            }
+                        },
+                        None => {
+                            let _tok = ui.begin_disabled(true);
+                            ui.checkbox(label, &mut false);
+                        },
+                    }
+                }
+            });
+
+            if let Some((idx, lit)) = toggled {
+                self.set_lit(idx, lit);
+                self.refresh();
+            }
+
+            if ui.button_with_size("Light all", [HALF_WIDTH, button_height]) {
+                self.light_all();
+            }
+            ui.same_line();
+            if ui.button_with_size("Refresh", [HALF_WIDTH, button_height]) {
+                self.refresh();
+            }
+
+            self.modal.close_button(ui);
+        }
+    }
+
+    fn interact(&mut self, ui: &imgui::Ui) {
+        self.modal.interact(ui);
+    }
+
+    fn log(&mut self, tx: Sender<String>) {
+        for x in self.logs.drain(..) {
+            tx.send(x).ok();
+        }
+    }
+}
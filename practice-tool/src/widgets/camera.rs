@@ -0,0 +1,249 @@
+use std::sync::Mutex;
+
+use libds3::prelude::*;
+use practice_tool_core::crossbeam_channel::Sender;
+use practice_tool_core::key::Key;
+use practice_tool_core::widgets::{scaling_factor, Widget, BUTTON_HEIGHT, BUTTON_WIDTH};
+use serde::Deserialize;
+
+/// Follow camera settings. The FOV is in degrees.
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq)]
+pub(crate) struct CameraSettings {
+    pub(crate) fov: f32,
+    pub(crate) distance: f32,
+    pub(crate) height: f32,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub(crate) struct CameraPreset {
+    pub(crate) name: String,
+    #[serde(flatten)]
+    pub(crate) settings: CameraSettings,
+}
+
+/// The widgets that can override the follow camera's FOV.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum FovOwner {
+    CameraTweaks,
+    FreeCamera,
+}
+
+/// The widget overriding the FOV. Both widgets write the FOV every frame, so
+/// only the owner does, and the other leaves it alone until it's released.
+static FOV_OWNER: Mutex<Option<FovOwner>> = Mutex::new(None);
+
+/// The widget overriding the FOV, if any.
+pub(crate) fn fov_owner() -> Option<FovOwner> {
+    FOV_OWNER.lock().ok().and_then(|owner| *owner)
+}
+
+/// Makes `owner` the widget overriding the FOV. Returns whether it is, i.e.
+/// `false` when the other widget already overrides it.
+pub(crate) fn claim_fov(owner: FovOwner) -> bool {
+    let Ok(mut current) = FOV_OWNER.lock() else { return false };
+    *current.get_or_insert(owner) == owner
+}
+
+pub(crate) fn release_fov(owner: FovOwner) {
+    if let Ok(mut current) = FOV_OWNER.lock() {
+        if *current == Some(owner) {
+            *current = None;
+        }
+    }
+}
+
+fn read_settings(camera: &FollowCamera) -> Option<CameraSettings> {
+    Some(CameraSettings {
+        fov: camera.fov.read()?.to_degrees(),
+        distance: camera.distance.read()?,
+        height: camera.height.read()?,
+    })
+}
+
+/// Writes the distance and height, and the FOV if `fov` is set.
+fn write_settings(camera: &FollowCamera, settings: &CameraSettings, fov: bool) -> Option<()> {
+    if fov {
+        camera.fov.write(settings.fov.to_radians())?;
+    }
+    camera.distance.write(settings.distance)?;
+    camera.height.write(settings.height)
+}
+
+/// Overrides the follow camera's FOV, distance and height. The values are
+/// written every frame while enabled, and the game's are restored when
+/// disabled. The FOV is left to the free camera while it overrides it.
+#[derive(Debug)]
+pub(crate) struct CameraTweaks {
+    camera: Option<FollowCamera>,
+    presets: Vec<CameraPreset>,
+    hotkey: Option<Key>,
+    label: String,
+
+    /// The game's settings when the override was enabled.
+    original: Option<CameraSettings>,
+    settings: Option<CameraSettings>,
+    /// Whether the FOV is overridden, see [`FovOwner`].
+    owns_fov: bool,
+    logs: Vec<String>,
+}
+
+impl CameraTweaks {
+    pub(crate) fn new(presets: Vec<CameraPreset>, hotkey: Option<Key>) -> Self {
+        let label = match hotkey {
+            Some(k) => format!("Camera tweaks ({k})"),
+            None => "Camera tweaks".to_string(),
+        };
+
+        CameraTweaks {
+            camera: FollowCamera::new(),
+            presets,
+            hotkey,
+            label,
+            original: None,
+            settings: None,
+            owns_fov: false,
+            logs: Vec::new(),
+        }
+    }
+
+    fn set_enabled(&mut self, enabled: bool) {
+        let Some(camera) = self.camera.as_ref() else {
+            self.logs.push("Camera tweaks aren't supported on this game version".to_string());
+            return;
+        };
+
+        if enabled {
+            let Some(settings) = read_settings(camera) else {
+                self.logs.push("Not enabling camera tweaks when not in game".to_string());
+                return;
+            };
+            self.original = Some(settings);
+            self.settings = Some(settings);
+        } else {
+            if let Some(original) = self.original.take() {
+                write_settings(camera, &original, self.owns_fov);
+            }
+            if self.owns_fov {
+                release_fov(FovOwner::CameraTweaks);
+                self.owns_fov = false;
+            }
+            self.settings = None;
+        }
+    }
+
+    /// Overrides the FOV once the free camera doesn't. The game's FOV is read
+    /// then, as the free camera restores it when it stops overriding it.
+    fn update_fov_owner(&mut self) {
+        if self.owns_fov || !claim_fov(FovOwner::CameraTweaks) {
+            return;
+        }
+
+        let (Some(camera), Some(original)) = (self.camera.as_ref(), self.original.as_mut()) else {
+            release_fov(FovOwner::CameraTweaks);
+            return;
+        };
+        match camera.fov.read() {
+            Some(fov) => {
+                original.fov = fov.to_degrees();
+                self.owns_fov = true;
+            },
+            None => release_fov(FovOwner::CameraTweaks),
+        }
+    }
+
+    fn apply_preset(&mut self, idx: usize) {
+        let preset = &self.presets[idx];
+        if self.settings.is_none() {
+            self.set_enabled(true);
+        }
+        if let Some(settings) = self.settings.as_mut() {
+            *settings = preset.settings;
+            self.logs.push(format!("Applied camera preset {}", preset.name));
+        }
+    }
+}
+
+impl Widget for CameraTweaks {
+    fn render(&mut self, ui: &imgui::Ui) {
+        let scale = scaling_factor(ui);
+        let button_width = BUTTON_WIDTH * scale;
+
+        let mut enabled = self.settings.is_some();
+        if ui.checkbox(&self.label, &mut enabled) {
+            self.set_enabled(enabled);
+        }
+
+        if let Some(settings) = self.settings.as_mut() {
+            if self.owns_fov {
+                ui.set_next_item_width(button_width);
+                ui.slider_config("##camera-fov", 10., 120.)
+                    .display_format("FOV %.0f")
+                    .build(&mut settings.fov);
+            } else {
+                ui.text_disabled("FOV set by the free camera");
+            }
+            ui.set_next_item_width(button_width);
+            ui.slider_config("##camera-distance", 0.5, 20.)
+                .display_format("Distance %.1f")
+                .build(&mut settings.distance);
+            ui.set_next_item_width(button_width);
+            ui.slider_config("##camera-height", -2., 5.)
+                .display_format("Height %.2f")
+                .build(&mut settings.height);
+        }
+
+        let mut applied = None;
+        for (idx, preset) in self.presets.iter().enumerate() {
+            let label = format!("{}##camera-preset-{idx}", preset.name);
+            if ui.button_with_size(label, [button_width, BUTTON_HEIGHT]) {
+                applied = Some(idx);
+            }
+        }
+        if let Some(idx) = applied {
+            self.apply_preset(idx);
+        }
+    }
+
+    fn interact(&mut self, ui: &imgui::Ui) {
+        if self.hotkey.map(|k| k.is_pressed(ui)).unwrap_or(false) {
+            self.set_enabled(self.settings.is_none());
+        }
+
+        if self.settings.is_some() {
+            self.update_fov_owner();
+        }
+
+        if let (Some(camera), Some(settings)) = (self.camera.as_ref(), self.settings.as_ref()) {
+            write_settings(camera, settings, self.owns_fov);
+        }
+    }
+
+    fn log(&mut self, tx: Sender<String>) {
+        for x in self.logs.drain(..) {
+            tx.send(x).ok();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fov_owner() {
+        assert_eq!(fov_owner(), None);
+
+        assert!(claim_fov(FovOwner::FreeCamera));
+        assert!(claim_fov(FovOwner::FreeCamera));
+        assert!(!claim_fov(FovOwner::CameraTweaks));
+        assert_eq!(fov_owner(), Some(FovOwner::FreeCamera));
+
+        // Only the owner releases the FOV.
+        release_fov(FovOwner::CameraTweaks);
+        assert_eq!(fov_owner(), Some(FovOwner::FreeCamera));
+        release_fov(FovOwner::FreeCamera);
+        assert!(claim_fov(FovOwner::CameraTweaks));
+        release_fov(FovOwner::CameraTweaks);
+        assert_eq!(fov_owner(), None);
+    }
+}
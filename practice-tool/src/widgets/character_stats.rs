@@ -1,57 +1,352 @@
 use libds3::prelude::*;
 use practice_tool_core::key::Key;
 use practice_tool_core::widgets::stats_editor::{Datum, Stats, StatsEditor};
+use practice_tool_core::widgets::store_value::{ReadWrite, StoreValue};
 use practice_tool_core::widgets::Widget;
+use serde::Deserialize;
+
+const MAX_STAT: i32 = 99;
+
+/// Starting classes. Stats can't go below the class' base stats.
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum Class {
+    Knight,
+    Mercenary,
+    Warrior,
+    Herald,
+    Thief,
+    Assassin,
+    Sorcerer,
+    Pyromancer,
+    Cleric,
+    Deprived,
+}
+
+impl Class {
+    /// Starting level and stats, in the order of [`stat_values`].
+    fn base(&self) -> (i32, [i32; 9]) {
+        match self {
+            Class::Knight => (9, [12, 10, 11, 15, 13, 12, 9, 9, 7]),
+            Class::Mercenary => (8, [11, 12, 11, 10, 10, 16, 10, 8, 9]),
+            Class::Warrior => (7, [14, 6, 12, 11, 16, 9, 8, 9, 11]),
+            Class::Herald => (9, [12, 10, 9, 12, 12, 11, 8, 13, 11]),
+            Class::Thief => (5, [10, 11, 10, 9, 9, 13, 10, 8, 14]),
+            Class::Assassin => (10, [10, 14, 11, 10, 10, 14, 11, 9, 10]),
+            Class::Sorcerer => (6, [9, 16, 9, 7, 7, 12, 16, 7, 12]),
+            Class::Pyromancer => (8, [11, 12, 10, 8, 12, 9, 14, 14, 7]),
+            Class::Cleric => (7, [10, 14, 9, 7, 12, 8, 7, 16, 13]),
+            Class::Deprived => (1, [10; 9]),
+        }
+    }
+}
+
+/// Vigor, attunement, endurance, vitality, strength, dexterity,
+/// intelligence, faith and luck.
+fn stat_values(stats: &mut CharacterStats) -> [&mut i32; 9] {
+    [
+        &mut stats.vigor,
+        &mut stats.attunement,
+        &mut stats.endurance,
+        &mut stats.vitality,
+        &mut stats.strength,
+        &mut stats.dexterity,
+        &mut stats.intelligence,
+        &mut stats.faith,
+        &mut stats.luck,
+    ]
+}
+
+/// Lowest value of each stat: the class' base stats, or 1 if the class is
+/// unknown.
+fn min_stats(class: Option<Class>) -> [i32; 9] {
+    class.map(|c| c.base().1).unwrap_or([1; 9])
+}
+
+/// Soul level matching the stats: the class' starting level, plus one for
+/// every point above its base stats. Every class' base stats add up to its
+/// starting level plus 89, so the class doesn't change the result.
+pub(crate) fn soul_level(stats: &CharacterStats) -> i32 {
+    let (level, base) = Class::Deprived.base();
+    let mut stats = stats.clone();
+    let points: i32 = stat_values(&mut stats).into_iter().zip(base).map(|(s, b)| *s - b).sum();
+
+    (level + points).max(1)
+}
+
+/// Clamps the stats between the class' base stats and 99, and sets the
+/// level accordingly.
+pub(crate) fn fix_level(class: Option<Class>, stats: &mut CharacterStats) {
+    for (s, min) in stat_values(stats).into_iter().zip(min_stats(class)) {
+        *s = (*s).clamp(min, MAX_STAT);
+    }
+    stats.level = soul_level(stats);
+}
+
+/// Stats to apply on top of the current ones. Stats left out are set to the
+/// class' base value if the class is given, and kept as they are otherwise.
+#[derive(Deserialize, Debug, Clone, Default)]
+pub(crate) struct StatsPreset {
+    pub(crate) vigor: Option<i32>,
+    pub(crate) attunement: Option<i32>,
+    pub(crate) endurance: Option<i32>,
+    pub(crate) vitality: Option<i32>,
+    pub(crate) strength: Option<i32>,
+    pub(crate) dexterity: Option<i32>,
+    pub(crate) intelligence: Option<i32>,
+    pub(crate) faith: Option<i32>,
+    pub(crate) luck: Option<i32>,
+}
+
+impl StatsPreset {
+    pub(crate) fn apply(&self, class: Option<Class>, stats: &mut CharacterStats) {
+        let preset = [
+            self.vigor,
+            self.attunement,
+            self.endurance,
+            self.vitality,
+            self.strength,
+            self.dexterity,
+            self.intelligence,
+            self.faith,
+            self.luck,
+        ];
+        let base = class.map(|c| c.base().1);
+
+        for (i, s) in stat_values(stats).into_iter().enumerate() {
+            if let Some(value) = preset[i].or(base.map(|b| b[i])) {
+                *s = value;
+            }
+        }
+
+        fix_level(class, stats);
+    }
+}
+
+/// Character data shown in the editor. Values that aren't `i32` in memory
+/// are widened for editing.
+#[derive(Debug, Clone)]
+struct CharacterData {
+    stats: CharacterStats,
+    ng_cycle: i32,
+    /// `None` when the value read is out of range, in which case it's
+    /// neither shown nor written.
+    hollowing: Option<i32>,
+}
 
 #[derive(Debug)]
 struct CharacterStatsEdit {
-    ptr: PointerChain<CharacterStats>,
-    stats: Option<CharacterStats>,
+    chains: CharacterChains,
+    data: Option<CharacterData>,
+    class: Option<Class>,
+}
+
+/// Pointer chains edited by the character stats editor.
+#[derive(Debug)]
+pub(crate) struct CharacterChains {
+    pub(crate) stats: PointerChain<CharacterStats>,
+    pub(crate) ng_cycle: PointerChain<u32>,
+    pub(crate) hollowing: PointerChain<u8>,
+}
+
+impl CharacterChains {
+    fn read(&self) -> Option<CharacterData> {
+        Some(CharacterData {
+            stats: self.stats.read()?,
+            ng_cycle: self.ng_cycle.read()? as i32,
+            hollowing: self
+                .hollowing
+                .read()
+                .map(i32::from)
+                .filter(|h| (0..=MAX_HOLLOWING).contains(h)),
+        })
+    }
 }
 
+const MAX_HOLLOWING: i32 = 99;
+
 impl Stats for CharacterStatsEdit {
     fn data(&mut self) -> Option<impl Iterator<Item = Datum>> {
-        self.stats.as_mut().map(|s| {
+        let [min_vig, min_att, min_end, min_vit, min_str, min_dex, min_int, min_fth, min_lck] =
+            min_stats(self.class);
+
+        self.data.as_mut().map(|d| {
+            let s = &mut d.stats;
             [
-                Datum::int("Level", &mut s.level, 1, i32::MAX),
-                Datum::int("Vigor", &mut s.vigor, 1, 99),
-                Datum::int("Attunement", &mut s.attunement, 1, 99),
-                Datum::int("Endurance", &mut s.endurance, 1, 99),
-                Datum::int("Strength", &mut s.strength, 1, 99),
-                Datum::int("Dexterity", &mut s.dexterity, 1, 99),
-                Datum::int("Intelligence", &mut s.intelligence, 1, 99),
-                Datum::int("Faith", &mut s.faith, 1, 99),
-                Datum::int("Luck", &mut s.luck, 1, 99),
-                Datum::int("Vitality", &mut s.vitality, 1, 99),
+                Datum::int("Vigor", &mut s.vigor, min_vig, MAX_STAT),
+                Datum::int("Attunement", &mut s.attunement, min_att, MAX_STAT),
+                Datum::int("Endurance", &mut s.endurance, min_end, MAX_STAT),
+                Datum::int("Strength", &mut s.strength, min_str, MAX_STAT),
+                Datum::int("Dexterity", &mut s.dexterity, min_dex, MAX_STAT),
+                Datum::int("Intelligence", &mut s.intelligence, min_int, MAX_STAT),
+                Datum::int("Faith", &mut s.faith, min_fth, MAX_STAT),
+                Datum::int("Luck", &mut s.luck, min_lck, MAX_STAT),
+                Datum::int("Vitality", &mut s.vitality, min_vit, MAX_STAT),
                 Datum::int("Souls", &mut s.souls, 1, i32::MAX),
+                Datum::int("Soul memory", &mut s.soul_memory, 0, i32::MAX),
+                // The game keeps counting past NG+7, where scaling stops.
+                Datum::int("NG cycle", &mut d.ng_cycle, 0, i32::MAX),
             ]
             .into_iter()
+            .chain(d.hollowing.as_mut().map(|h| Datum::int("Hollowing", h, 0, MAX_HOLLOWING)))
         })
     }
 
     fn read(&mut self) {
-        self.stats = self.ptr.read();
+        self.data = self.chains.read();
     }
 
     fn write(&mut self) {
-        if let Some(stats) = self.stats.clone() {
-            self.ptr.write(stats);
+        if let Some(mut data) = self.data.clone() {
+            fix_level(self.class, &mut data.stats);
+            self.chains.stats.write(data.stats);
+            self.chains.ng_cycle.write(data.ng_cycle as u32);
+            if let Some(hollowing) = data.hollowing {
+                self.chains.hollowing.write(hollowing as u8);
+            }
         }
     }
 
     fn clear(&mut self) {
-        self.stats = None;
+        self.data = None;
     }
 }
 
 pub(crate) fn character_stats_edit(
-    character_stats: PointerChain<CharacterStats>,
+    chains: CharacterChains,
+    class: Option<Class>,
     key_open: Option<Key>,
     key_close: Key,
 ) -> Box<dyn Widget> {
     Box::new(StatsEditor::new(
-        CharacterStatsEdit { ptr: character_stats, stats: None },
+        CharacterStatsEdit { chains, data: None, class },
         key_open,
         Some(key_close),
     ))
 }
+
+#[derive(Debug)]
+struct ApplyStatsPreset {
+    ptr: PointerChain<CharacterStats>,
+    stats: Option<CharacterStats>,
+    class: Option<Class>,
+    preset: StatsPreset,
+    label: String,
+}
+
+impl ReadWrite for ApplyStatsPreset {
+    fn read(&mut self) -> bool {
+        self.stats = self.ptr.read();
+        self.stats.is_some()
+    }
+
+    fn write(&mut self) {
+        if let Some(mut stats) = self.stats.take() {
+            self.preset.apply(self.class, &mut stats);
+            self.ptr.write(stats);
+        }
+    }
+
+    fn label(&self) -> &str {
+        &self.label
+    }
+}
+
+pub(crate) fn stats_preset(
+    name: &str,
+    character_stats: PointerChain<CharacterStats>,
+    class: Option<Class>,
+    preset: StatsPreset,
+    key: Option<Key>,
+) -> Box<dyn Widget> {
+    Box::new(StoreValue::new(
+        ApplyStatsPreset {
+            ptr: character_stats,
+            stats: None,
+            class,
+            preset,
+            label: format!("Stats: {name}"),
+        },
+        key,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stats(class: Class) -> CharacterStats {
+        let (
+            level,
+            [vigor, attunement, endurance, vitality, strength, dexterity, intelligence, faith, luck],
+        ) = class.base();
+
+        CharacterStats {
+            vigor,
+            attunement,
+            endurance,
+            strength,
+            dexterity,
+            intelligence,
+            faith,
+            luck,
+            unk1: 0,
+            unk2: 0,
+            vitality,
+            level,
+            souls: 0,
+            soul_memory: 0,
+        }
+    }
+
+    #[test]
+    fn test_soul_level() {
+        for class in [
+            Class::Knight,
+            Class::Mercenary,
+            Class::Warrior,
+            Class::Herald,
+            Class::Thief,
+            Class::Assassin,
+            Class::Sorcerer,
+            Class::Pyromancer,
+            Class::Cleric,
+            Class::Deprived,
+        ] {
+            let mut s = stats(class);
+            assert_eq!(soul_level(&s), s.level, "{class:?}");
+
+            s.vigor += 17;
+            s.dexterity += 3;
+            assert_eq!(soul_level(&s), class.base().0 + 20, "{class:?}");
+        }
+
+        let mut s = stats(Class::Knight);
+        s.vigor = 99;
+        s.luck = 1;
+        fix_level(Some(Class::Knight), &mut s);
+        assert_eq!((s.vigor, s.luck, s.level), (99, 7, 96));
+
+        fix_level(None, &mut s);
+        assert_eq!(s.level, 96);
+    }
+
+    #[test]
+    fn test_stats_preset() {
+        let preset: StatsPreset = toml::from_str("vigor = 27\ndexterity = 40").unwrap();
+
+        let mut s = stats(Class::Thief);
+        s.luck = 40;
+        preset.apply(Some(Class::Thief), &mut s);
+        assert_eq!((s.vigor, s.dexterity, s.luck, s.level), (27, 40, 14, 49));
+
+        let mut s = stats(Class::Thief);
+        s.luck = 40;
+        preset.apply(None, &mut s);
+        assert_eq!((s.vigor, s.dexterity, s.luck, s.level), (27, 40, 40, 75));
+
+        let mut s = stats(Class::Sorcerer);
+        StatsPreset::default().apply(Some(Class::Deprived), &mut s);
+        assert_eq!(s.level, 1);
+    }
+}
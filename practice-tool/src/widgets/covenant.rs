@@ -0,0 +1,98 @@
+use libds3::prelude::*;
+use practice_tool_core::crossbeam_channel::Sender;
+use practice_tool_core::widgets::{scaling_factor, Widget, BUTTON_WIDTH};
+
+use crate::widgets::item_spawn::item_name;
+
+const ITEM_CATEGORY_MASK: u32 = 0xF000_0000;
+/// Covenant items are accessories.
+const ITEM_CATEGORY_ACCESSORY: u32 = 0x2000_0000;
+
+/// Shows the player's covenant by name and switches it.
+///
+/// The covenant is both a byte in `PlayerGameData` and the item equipped in
+/// the covenant slot. The byte is only trusted when it agrees with the slot,
+/// which checks its offset, and switching equips the covenant's item through
+/// the game's equip routine, so the two stay consistent.
+#[derive(Debug)]
+pub(crate) struct Covenant {
+    ptr: PointerChain<u8>,
+    inventory: Inventory,
+    logs: Vec<String>,
+}
+
+impl Covenant {
+    pub(crate) fn new(ptr: PointerChain<u8>, inventory: Inventory) -> Self {
+        Covenant { ptr, inventory, logs: Vec::new() }
+    }
+
+    /// The current covenant, as an index in [`COVENANTS`]. Returns `None`
+    /// when not in game, or when the value read doesn't match the covenant
+    /// item equipped.
+    fn current(&self) -> Option<usize> {
+        let idx = self.ptr.read().map(usize::from).filter(|&idx| idx < COVENANTS.len())?;
+        let equipped = self.inventory.equipped()?;
+
+        let slot = EquipSlot::Covenant as usize;
+        let consistent = if equipped.inventory_idx[slot] < 0 {
+            idx == 0
+        } else {
+            item_name(equipped.item_ids[slot]) == COVENANTS[idx]
+        };
+
+        consistent.then_some(idx)
+    }
+
+    /// Equips the item of the covenant `idx` from the inventory, then
+    /// updates the covenant.
+    fn set(&self, idx: usize) -> Result<(), String> {
+        let name = COVENANTS[idx];
+        if idx == 0 {
+            return Err("Leave the covenant from the equipment menu".to_string());
+        }
+
+        let item = self
+            .inventory
+            .items()
+            .ok_or("Not switching covenant when not in game")?
+            .into_iter()
+            .find(|item| {
+                item.entry.item_id & ITEM_CATEGORY_MASK == ITEM_CATEGORY_ACCESSORY
+                    && item_name(item.entry.item_id) == name
+            })
+            .ok_or_else(|| format!("The {name} covenant item isn't in the inventory"))?;
+
+        self.inventory
+            .equip(EquipSlot::Covenant, item.idx)
+            .ok_or_else(|| format!("Couldn't equip the {name} covenant item"))?;
+        self.ptr.write(idx as u8).ok_or("Couldn't set covenant")?;
+        Ok(())
+    }
+}
+
+impl Widget for Covenant {
+    fn render(&mut self, ui: &imgui::Ui) {
+        let scale = scaling_factor(ui);
+
+        let Some(mut idx) = self.current() else {
+            ui.text_disabled("Covenant");
+            return;
+        };
+
+        ui.set_next_item_width(BUTTON_WIDTH * scale);
+        if ui.combo_simple_string("##covenant", &mut idx, &COVENANTS) {
+            match self.set(idx) {
+                Ok(()) => self.logs.push(format!("Covenant set to {}", COVENANTS[idx])),
+                Err(e) => self.logs.push(e),
+            }
+        }
+    }
+
+    fn interact(&mut self, _: &imgui::Ui) {}
+
+    fn log(&mut self, tx: Sender<String>) {
+        for x in self.logs.drain(..) {
+            tx.send(x).ok();
+        }
+    }
+}
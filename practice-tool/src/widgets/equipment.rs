@@ -0,0 +1,441 @@
+//! Named sets of equipped items, saved from the current equipment and
+//! re-equipped in one go.
+//!
+//! Loadouts are stored in `jdsd_dsiii_practice_tool_equipment.toml`, next to
+//! the practice tool's DLL:
+//!
+//! ```toml
+//! [[loadout]]
+//! name = "Pontiff"
+//! spells = [3000, 3010]
+//! items = [
+//!   { slot = "right_hand_1", id = 0x000F4240 },
+//!   { slot = "ring_1", id = 0x20004E2A },
+//! ]
+//! ```
+//!
+//! Items missing from the inventory are spawned before being equipped.
+//! `spells` are `Magic` row ids, attuned in order; without it, the attuned
+//! spells are left as they are.
+
+use imgui::InputText;
+use libds3::prelude::*;
+use practice_tool_core::crossbeam_channel::Sender;
+use practice_tool_core::key::Key;
+use practice_tool_core::widgets::{scaling_factor, Widget, BUTTON_HEIGHT, BUTTON_WIDTH};
+use serde::{Deserialize, Serialize};
+
+use crate::widgets::item_spawn::{item_name, ItemSpawnInstance};
+use crate::widgets::loadout_file::LoadoutFile;
+use crate::widgets::modal::{button_height, Modal, HALF_WIDTH, MODAL_WIDTH};
+
+pub(crate) const EQUIPMENT_FILE: &str = "jdsd_dsiii_practice_tool_equipment.toml";
+
+const EQM_TAG: &str = "##equipment";
+
+/// An equip slot, written as its snake case label (e.g. `right_hand_1`).
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub(crate) struct Slot(pub(crate) EquipSlot);
+
+fn slot_key(slot: EquipSlot) -> String {
+    slot.label().to_lowercase().replace(' ', "_")
+}
+
+impl TryFrom<String> for Slot {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        EquipSlot::ALL
+            .into_iter()
+            .find(|&slot| slot_key(slot) == value)
+            .map(Slot)
+            .ok_or_else(|| format!("Unrecognized equip slot: {value}"))
+    }
+}
+
+impl From<Slot> for String {
+    fn from(value: Slot) -> Self {
+        slot_key(value.0)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub(crate) struct EquippedItem {
+    pub(crate) slot: Slot,
+    pub(crate) id: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct EquipmentLoadout {
+    pub(crate) name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) spells: Option<Vec<u32>>,
+    #[serde(default)]
+    pub(crate) items: Vec<EquippedItem>,
+}
+
+/// The equipment loadouts, in [`EQUIPMENT_FILE`].
+pub(crate) type EquipmentLoadouts = LoadoutFile<EquipmentLoadout>;
+
+impl EquipmentLoadouts {
+    pub(crate) fn get(&self, name: &str) -> Option<&EquipmentLoadout> {
+        self.loadouts().iter().find(|l| l.name == name)
+    }
+
+    /// Stores a loadout, replacing the one with the same name if any.
+    pub(crate) fn set(&mut self, loadout: EquipmentLoadout) {
+        let loadouts = self.loadouts_mut();
+        match loadouts.iter_mut().find(|l| l.name == loadout.name) {
+            Some(l) => *l = loadout,
+            None => loadouts.push(loadout),
+        }
+    }
+
+    pub(crate) fn remove(&mut self, name: &str) {
+        self.loadouts_mut().retain(|l| l.name != name);
+    }
+}
+
+/// Reads and writes the equipped items.
+#[derive(Debug)]
+pub(crate) struct Equipper {
+    inventory: Inventory,
+    func_ptr: usize,
+    map_item_man: usize,
+}
+
+impl Equipper {
+    pub(crate) fn new(inventory: Inventory, func_ptr: usize, map_item_man: usize) -> Self {
+        Equipper { inventory, func_ptr, map_item_man }
+    }
+
+    /// The items equipped right now. Returns `None` when not in game.
+    fn current(&self) -> Option<Vec<EquippedItem>> {
+        let equipped = self.inventory.equipped()?;
+
+        Some(
+            EquipSlot::ALL
+                .into_iter()
+                .filter(|&slot| equipped.inventory_idx[slot as usize] >= 0)
+                .map(|slot| EquippedItem { slot: Slot(slot), id: equipped.item_ids[slot as usize] })
+                .collect(),
+        )
+    }
+
+    /// Index of an inventory entry holding `item_id` that isn't equipped in
+    /// another slot. Returns `None` if every copy is, e.g. for a second copy
+    /// of a ring, so that a new one is spawned instead of two slots sharing
+    /// an entry.
+    fn find(&self, equipped: &Equipped, slot: EquipSlot, item_id: u32) -> Option<usize> {
+        self.inventory
+            .items()?
+            .into_iter()
+            .filter(|item| item.entry.item_id == item_id)
+            .map(|item| item.idx)
+            .find(|&idx| equipped.slot_of(idx).map(|s| s == slot).unwrap_or(true))
+    }
+
+    /// The attuned spells. Returns `None` when they can't be read.
+    fn current_spells(&self) -> Option<Vec<u32>> {
+        let spells = self.inventory.attuned_spells(&PARAMS.read())?;
+        Some(spells.into_iter().flatten().collect())
+    }
+
+    /// Equips every item of the loadout through the game's equip routine,
+    /// spawning the ones that aren't in the inventory, then attunes the
+    /// loadout's spells. Returns a log line per item that couldn't be
+    /// equipped.
+    fn equip(&self, loadout: &EquipmentLoadout) -> Result<Vec<String>, String> {
+        self.inventory
+            .check_layout()
+            .ok_or("Not equipping: not in game, or inventory layout not recognized")?;
+        if !self.inventory.can_equip() {
+            return Err("Not equipping: the game's equip routine wasn't found".to_string());
+        }
+
+        let mut errors = Vec::new();
+
+        for item in &loadout.items {
+            let slot = item.slot.0;
+            let equipped = self.inventory.equipped().ok_or("Not equipping when not in game")?;
+            let idx = self.find(&equipped, slot, item.id).or_else(|| {
+                let i = ItemSpawnInstance {
+                    spawn_item_func_ptr: self.func_ptr as _,
+                    map_item_man: self.map_item_man as _,
+                    qty: 1,
+                    durability: 100,
+                    item_id: item.id,
+                    infusion: 0,
+                    upgrade: 0,
+                };
+                unsafe { i.spawn() };
+
+                self.find(&equipped, slot, item.id)
+            });
+
+            let result = idx.and_then(|idx| {
+                if equipped.inventory_idx[slot as usize] == idx as i32 {
+                    Some(())
+                } else {
+                    self.inventory.equip(slot, idx)
+                }
+            });
+            if result.is_none() {
+                errors.push(format!("Couldn't equip {}", item_name(item.id)));
+            }
+        }
+
+        if let Some(spells) = &loadout.spells {
+            if self.inventory.set_attuned_spells(&PARAMS.read(), spells).is_none() {
+                errors.push("Couldn't attune spells".to_string());
+            }
+        }
+
+        Ok(errors)
+    }
+}
+
+/// Saves, deletes and equips loadouts.
+#[derive(Debug)]
+pub(crate) struct EquipmentManager {
+    equipper: Equipper,
+    loadouts: EquipmentLoadouts,
+    modal: Modal,
+
+    selected: usize,
+    name: String,
+    logs: Vec<String>,
+}
+
+impl EquipmentManager {
+    pub(crate) fn new(
+        equipper: Equipper,
+        loadouts: EquipmentLoadouts,
+        hotkey_open: Option<Key>,
+        hotkey_close: Key,
+    ) -> Self {
+        EquipmentManager {
+            equipper,
+            loadouts,
+            modal: Modal::new("Equipment", EQM_TAG, hotkey_open, hotkey_close),
+            selected: 0,
+            name: String::new(),
+            logs: Vec::new(),
+        }
+    }
+
+    fn save_current(&mut self) {
+        let name = self.name.trim().to_string();
+        if name.is_empty() {
+            self.logs.push("Enter a loadout name first".into());
+            return;
+        }
+
+        let Some(items) = self.equipper.current() else {
+            self.logs.push("Not saving equipment when not in game".into());
+            return;
+        };
+
+        let spells = self.equipper.current_spells();
+        let spells_note = if spells.is_none() { " (attuned spells couldn't be read)" } else { "" };
+
+        self.loadouts.set(EquipmentLoadout { name: name.clone(), spells, items });
+        self.selected =
+            self.loadouts.loadouts().iter().position(|l| l.name == name).unwrap_or_default();
+
+        match self.loadouts.save() {
+            Ok(()) => self.logs.push(format!("Saved equipment {name}{spells_note}")),
+            Err(e) => self.logs.push(e),
+        }
+    }
+
+    fn equip_selected(&mut self) {
+        let Some(loadout) = self.loadouts.loadouts().get(self.selected) else {
+            return;
+        };
+
+        match self.equipper.equip(loadout) {
+            Ok(errors) => {
+                self.logs.push(format!("Equipped {}", loadout.name));
+                self.logs.extend(errors);
+            },
+            Err(e) => self.logs.push(e),
+        }
+    }
+
+    fn delete_selected(&mut self) {
+        let Some(name) = self.loadouts.loadouts().get(self.selected).map(|l| l.name.clone()) else {
+            return;
+        };
+
+        self.loadouts.remove(&name);
+        self.selected = 0;
+
+        match self.loadouts.save() {
+            Ok(()) => self.logs.push(format!("Deleted equipment {name}")),
+            Err(e) => self.logs.push(e),
+        }
+    }
+}
+
+impl Widget for EquipmentManager {
+    fn render(&mut self, ui: &imgui::Ui) {
+        self.modal.open_button(ui);
+
+        if let Some(_token) = self.modal.begin(ui) {
+            let button_height = button_height(ui);
+
+            if !self.loadouts.loadouts().is_empty() {
+                ui.set_next_item_width(MODAL_WIDTH);
+                ui.combo(
+                    "##equipment-loadout",
+                    &mut self.selected,
+                    self.loadouts.loadouts(),
+                    |loadout| loadout.name.as_str().into(),
+                );
+
+                if ui.button_with_size("Equip", [HALF_WIDTH, button_height]) {
+                    self.equip_selected();
+                }
+                ui.same_line();
+                if ui.button_with_size("Delete", [HALF_WIDTH, button_height]) {
+                    self.delete_selected();
+                }
+
+                ui.separator();
+            }
+
+            ui.set_next_item_width(HALF_WIDTH);
+            InputText::new(ui, "##equipment-name", &mut self.name).hint("Loadout name...").build();
+            ui.same_line();
+            if ui.button_with_size("Save current", [HALF_WIDTH, button_height]) {
+                self.save_current();
+            }
+
+            self.modal.close_button(ui);
+        }
+    }
+
+    fn interact(&mut self, ui: &imgui::Ui) {
+        self.modal.interact(ui);
+    }
+
+    fn log(&mut self, tx: Sender<String>) {
+        for x in self.logs.drain(..) {
+            tx.send(x).ok();
+        }
+    }
+}
+
+/// Equips a loadout by name. The file is read again at every use, so that
+/// loadouts saved from the equipment manager are picked up.
+#[derive(Debug)]
+pub(crate) struct EquipLoadout {
+    equipper: Equipper,
+    name: String,
+    hotkey: Option<Key>,
+    label: String,
+    logs: Vec<String>,
+}
+
+impl EquipLoadout {
+    pub(crate) fn new(equipper: Equipper, name: String, hotkey: Option<Key>) -> Self {
+        let label = match hotkey {
+            Some(k) => format!("Equip {name} ({k})"),
+            None => format!("Equip {name}"),
+        };
+
+        EquipLoadout { equipper, name, hotkey, label, logs: Vec::new() }
+    }
+
+    fn equip(&mut self) {
+        let loadouts = EquipmentLoadouts::load_default(EQUIPMENT_FILE);
+        let Some(loadout) = loadouts.get(&self.name) else {
+            self.logs.push(format!("No equipment loadout named {}", self.name));
+            return;
+        };
+
+        match self.equipper.equip(loadout) {
+            Ok(errors) => {
+                self.logs.push(format!("Equipped {}", self.name));
+                self.logs.extend(errors);
+            },
+            Err(e) => self.logs.push(e),
+        }
+    }
+}
+
+impl Widget for EquipLoadout {
+    fn render(&mut self, ui: &imgui::Ui) {
+        let scale = scaling_factor(ui);
+
+        if ui.button_with_size(&self.label, [BUTTON_WIDTH * scale, BUTTON_HEIGHT]) {
+            self.equip();
+        }
+    }
+
+    fn interact(&mut self, ui: &imgui::Ui) {
+        if self.hotkey.map(|k| k.is_pressed(ui)).unwrap_or(false) {
+            self.equip();
+        }
+    }
+
+    fn log(&mut self, tx: Sender<String>) {
+        for x in self.logs.drain(..) {
+            tx.send(x).ok();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_serialize() {
+        let parsed = EquipmentLoadouts::parse(
+            r#"
+            [[loadout]]
+            name = "Pontiff"
+            spells = [3000, 3010]
+            items = [
+              { slot = "right_hand_1", id = 0x000F4240 },
+              { slot = "ring_1", id = 0x20004E2A },
+            ]
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(parsed, vec![EquipmentLoadout {
+            name: "Pontiff".to_string(),
+            spells: Some(vec![3000, 3010]),
+            items: vec![
+                EquippedItem { slot: Slot(EquipSlot::RightHand1), id: 0x000F4240 },
+                EquippedItem { slot: Slot(EquipSlot::Ring1), id: 0x20004E2A },
+            ],
+        }]);
+
+        let mut loadouts = EquipmentLoadouts::default();
+        loadouts.set(parsed[0].clone());
+        loadouts.set(EquipmentLoadout {
+            name: "Pontiff".to_string(),
+            spells: None,
+            items: Vec::new(),
+        });
+        assert_eq!(loadouts.loadouts().len(), 1);
+        assert!(loadouts.loadouts()[0].items.is_empty());
+
+        loadouts.set(parsed[0].clone());
+        assert_eq!(EquipmentLoadouts::parse(&loadouts.serialize().unwrap()).unwrap(), parsed);
+
+        assert!(EquipmentLoadouts::parse(
+            "[[loadout]]\nname = \"a\"\nitems = [{ slot = \"tail\", id = 1 }]"
+        )
+        .is_err());
+
+        // Loadouts saved without spells leave the attuned ones alone.
+        let parsed = EquipmentLoadouts::parse("[[loadout]]\nname = \"a\"").unwrap();
+        assert_eq!(parsed[0].spells, None);
+    }
+}
@@ -0,0 +1,304 @@
+use libds3::prelude::*;
+use practice_tool_core::crossbeam_channel::Sender;
+use practice_tool_core::key::Key;
+use practice_tool_core::widgets::{scaling_factor, Widget, BUTTON_HEIGHT, BUTTON_WIDTH};
+use serde::Deserialize;
+
+use crate::widgets::camera::{claim_fov, fov_owner, release_fov, FovOwner};
+
+/// Speed multiplier while the `fast` key is held.
+const FAST_MULTIPLIER: f32 = 4.;
+
+/// Keys held to move the free camera. The defaults are on the numpad, away
+/// from the game's and the tool's keys.
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(default)]
+pub(crate) struct MovementKeys {
+    pub(crate) forward: Option<Key>,
+    pub(crate) back: Option<Key>,
+    pub(crate) left: Option<Key>,
+    pub(crate) right: Option<Key>,
+    pub(crate) up: Option<Key>,
+    pub(crate) down: Option<Key>,
+    pub(crate) fast: Option<Key>,
+}
+
+impl Default for MovementKeys {
+    fn default() -> Self {
+        MovementKeys {
+            forward: "numpad8".parse().ok(),
+            back: "numpad5".parse().ok(),
+            left: "numpad4".parse().ok(),
+            right: "numpad6".parse().ok(),
+            up: "numpad9".parse().ok(),
+            down: "numpad7".parse().ok(),
+            fast: "numpad0".parse().ok(),
+        }
+    }
+}
+
+impl MovementKeys {
+    /// Sum of the directions held, as right, up and forward components.
+    /// Nothing is held while typing in the tool.
+    pub(crate) fn direction(&self, ui: &imgui::Ui) -> [f32; 3] {
+        if ui.io().want_capture_keyboard {
+            return [0.; 3];
+        }
+
+        [
+            (self.right, [1., 0., 0.]),
+            (self.left, [-1., 0., 0.]),
+            (self.up, [0., 1., 0.]),
+            (self.down, [0., -1., 0.]),
+            (self.forward, [0., 0., 1.]),
+            (self.back, [0., 0., -1.]),
+        ]
+        .into_iter()
+        .filter(|(key, _)| is_down(*key, ui))
+        .fold([0.; 3], |acc, (_, d)| [acc[0] + d[0], acc[1] + d[1], acc[2] + d[2]])
+    }
+
+    /// Distance covered this frame at `speed` units per second.
+    pub(crate) fn distance(&self, ui: &imgui::Ui, speed: f32) -> f32 {
+        let fast = !ui.io().want_capture_keyboard && is_down(self.fast, ui);
+        let multiplier = if fast { FAST_MULTIPLIER } else { 1. };
+        speed * multiplier * ui.io().delta_time
+    }
+}
+
+fn is_down(key: Option<Key>, ui: &imgui::Ui) -> bool {
+    key.map(|k| k.is_down(ui)).unwrap_or(false)
+}
+
+/// Moves `position` by `distance` along `direction`, given as right, up and
+/// forward components. Up is the world's, so that the camera rises straight
+/// up whatever it's looking at.
+pub(crate) fn step(
+    position: [f32; 3],
+    matrix: &CameraMatrix,
+    [right, up, forward]: [f32; 3],
+    distance: f32,
+) -> [f32; 3] {
+    let mut delta: [f32; 3] =
+        std::array::from_fn(|i| matrix.right[i] * right + matrix.forward[i] * forward);
+    delta[1] += up;
+
+    let length = delta.iter().map(|d| d * d).sum::<f32>().sqrt();
+    if length < f32::EPSILON {
+        return position;
+    }
+
+    let [x, y, z] = position;
+    let [dx, dy, dz] = delta.map(|d| d / length * distance);
+    [x + dx, y + dy, z + dz]
+}
+
+/// A camera detached from the player, moved with the [`MovementKeys`].
+#[derive(Debug)]
+pub(crate) struct FreeCamera {
+    camera: Option<Camera>,
+    follow_camera: Option<FollowCamera>,
+    player_position: PointerChain<[f32; 3]>,
+    keys: MovementKeys,
+    hotkey: Option<Key>,
+    hotkey_teleport: Option<Key>,
+    label: String,
+    label_teleport: String,
+
+    enabled: bool,
+    position: [f32; 3],
+    /// Units per second.
+    speed: f32,
+    /// FOV override in degrees, and the game's FOV to restore, in radians.
+    /// Only set while the FOV is claimed, see [`FovOwner`].
+    fov: Option<(f32, f32)>,
+    logs: Vec<String>,
+}
+
+impl FreeCamera {
+    pub(crate) fn new(
+        player_position: PointerChain<[f32; 3]>,
+        speed: f32,
+        keys: MovementKeys,
+        hotkey: Option<Key>,
+        hotkey_teleport: Option<Key>,
+    ) -> Self {
+        let label = match hotkey {
+            Some(k) => format!("Free camera ({k})"),
+            None => "Free camera".to_string(),
+        };
+        let label_teleport = match hotkey_teleport {
+            Some(k) => format!("Teleport to camera ({k})"),
+            None => "Teleport to camera".to_string(),
+        };
+
+        FreeCamera {
+            camera: Camera::new(),
+            follow_camera: FollowCamera::new(),
+            player_position,
+            keys,
+            hotkey,
+            hotkey_teleport,
+            label,
+            label_teleport,
+            enabled: false,
+            position: [0.; 3],
+            speed,
+            fov: None,
+            logs: Vec::new(),
+        }
+    }
+
+    fn set_enabled(&mut self, enabled: bool) {
+        let Some(camera) = self.camera.as_ref().filter(|c| c.can_detach()) else {
+            self.logs.push("Free camera isn't supported on this game version".to_string());
+            return;
+        };
+
+        if enabled {
+            let Some(matrix) = camera.matrix() else {
+                self.logs.push("Not enabling free camera when not in game".to_string());
+                return;
+            };
+            let [x, y, z, _] = matrix.position;
+            self.position = [x, y, z];
+        }
+
+        match camera.set_detached(enabled) {
+            Some(()) => self.enabled = enabled,
+            None => self.logs.push("Couldn't detach the camera".to_string()),
+        }
+
+        if !self.enabled {
+            self.restore_fov();
+        }
+    }
+
+    /// The game's FOV, in radians.
+    fn game_fov(&self) -> Option<f32> {
+        self.follow_camera.as_ref().and_then(|c| c.fov.read())
+    }
+
+    fn restore_fov(&mut self) {
+        if let (Some(camera), Some((_, original))) = (self.follow_camera.as_ref(), self.fov.take())
+        {
+            camera.fov.write(original);
+        }
+        release_fov(FovOwner::FreeCamera);
+    }
+
+    fn teleport(&mut self) {
+        if !self.enabled {
+            self.logs.push("Enable the free camera first".to_string());
+            return;
+        }
+
+        match self.player_position.write(self.position) {
+            Some(()) => self.logs.push("Teleported to camera".to_string()),
+            None => self.logs.push("Couldn't teleport".to_string()),
+        }
+    }
+
+    fn update(&mut self, ui: &imgui::Ui) {
+        // The game resets the FOV of the follow camera at times, so it's
+        // written every frame.
+        if let (Some(camera), Some((fov, _))) = (self.follow_camera.as_ref(), self.fov) {
+            camera.fov.write(fov.to_radians());
+        }
+
+        let Some(camera) = self.camera.as_ref() else { return };
+        let Some(matrix) = camera.matrix() else { return };
+
+        let direction = self.keys.direction(ui);
+        let distance = self.keys.distance(ui, self.speed);
+
+        self.position = step(self.position, &matrix, direction, distance);
+        camera.set_position(self.position);
+    }
+}
+
+impl Widget for FreeCamera {
+    fn render(&mut self, ui: &imgui::Ui) {
+        let scale = scaling_factor(ui);
+        let button_width = BUTTON_WIDTH * scale;
+
+        let mut enabled = self.enabled;
+        if ui.checkbox(&self.label, &mut enabled) {
+            self.set_enabled(enabled);
+        }
+
+        if !self.enabled {
+            return;
+        }
+
+        ui.set_next_item_width(button_width);
+        ui.slider("##free-camera-speed", 1., 100., &mut self.speed);
+
+        // The FOV is only overridden once it's changed from the game's, and
+        // left to the camera tweaks while they override it.
+        let fov = self.fov.or_else(|| self.game_fov().map(|fov| (fov.to_degrees(), fov)));
+        if fov_owner() == Some(FovOwner::CameraTweaks) {
+            ui.text_disabled("FOV set by the camera tweaks");
+        } else if let Some((mut fov_deg, original)) = fov {
+            ui.set_next_item_width(button_width);
+            if ui
+                .slider_config("##free-camera-fov", 10., 120.)
+                .display_format("FOV %.0f")
+                .build(&mut fov_deg)
+                && claim_fov(FovOwner::FreeCamera)
+            {
+                self.fov = Some((fov_deg, original));
+            }
+        }
+
+        if ui.button_with_size(&self.label_teleport, [button_width, BUTTON_HEIGHT]) {
+            self.teleport();
+        }
+    }
+
+    fn interact(&mut self, ui: &imgui::Ui) {
+        if self.hotkey.map(|k| k.is_pressed(ui)).unwrap_or(false) {
+            self.set_enabled(!self.enabled);
+        }
+
+        if self.hotkey_teleport.map(|k| k.is_pressed(ui)).unwrap_or(false) {
+            self.teleport();
+        }
+
+        if self.enabled {
+            self.update(ui);
+        }
+    }
+
+    fn log(&mut self, tx: Sender<String>) {
+        for x in self.logs.drain(..) {
+            tx.send(x).ok();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_step() {
+        // Looking along +z, with +x on the right.
+        let matrix = CameraMatrix {
+            right: [1., 0., 0., 0.],
+            up: [0., 1., 0., 0.],
+            forward: [0., 0., 1., 0.],
+            position: [0., 0., 0., 1.],
+        };
+
+        assert_eq!(step([1., 2., 3.], &matrix, [0., 0., 1.], 2.), [1., 2., 5.]);
+        assert_eq!(step([1., 2., 3.], &matrix, [-1., 0., 0.], 2.), [-1., 2., 3.]);
+        assert_eq!(step([1., 2., 3.], &matrix, [0., 1., 0.], 2.), [1., 4., 3.]);
+        assert_eq!(step([1., 2., 3.], &matrix, [0., 0., 0.], 2.), [1., 2., 3.]);
+
+        // Diagonals move by the same distance.
+        let [x, y, z] = step([0.; 3], &matrix, [1., 0., 1.], 2.);
+        assert!(((x * x + y * y + z * z).sqrt() - 2.).abs() < 1e-5);
+        assert!((x - z).abs() < 1e-5);
+    }
+}
@@ -0,0 +1,249 @@
+use libds3::prelude::*;
+use practice_tool_core::crossbeam_channel::Sender;
+use practice_tool_core::key::Key;
+use practice_tool_core::widgets::Widget;
+
+use crate::widgets::item_spawn::{item_name, ItemSpawnInstance};
+use crate::widgets::modal::{button_height, filter_input, Modal, HALF_WIDTH, MODAL_WIDTH};
+
+const INV_TAG: &str = "##inventory";
+
+#[derive(Debug)]
+pub(crate) struct InventoryEditor {
+    inventory: Inventory,
+    func_ptr: usize,
+    map_item_man: usize,
+    modal: Modal,
+
+    items: Vec<(InventoryItem, String)>,
+    selected: Option<usize>,
+    quantity: u32,
+    durability: u32,
+    filter_string: String,
+    logs: Vec<String>,
+}
+
+impl InventoryEditor {
+    pub(crate) fn new(
+        inventory: Inventory,
+        func_ptr: usize,
+        map_item_man: usize,
+        hotkey_open: Option<Key>,
+        hotkey_close: Key,
+    ) -> Self {
+        InventoryEditor {
+            inventory,
+            func_ptr,
+            map_item_man,
+            modal: Modal::new("Inventory", INV_TAG, hotkey_open, hotkey_close),
+            items: Vec::new(),
+            selected: None,
+            quantity: 1,
+            durability: 0,
+            filter_string: String::new(),
+            logs: Vec::new(),
+        }
+    }
+
+    fn refresh(&mut self) {
+        self.items = self
+            .inventory
+            .items()
+            .unwrap_or_default()
+            .into_iter()
+            .map(|item| {
+                let name = item_name(item.entry.item_id);
+                (item, name)
+            })
+            .collect();
+
+        if let Some(item) = self.selected_item() {
+            self.select(item);
+        } else {
+            self.selected = None;
+        }
+    }
+
+    fn select(&mut self, item: InventoryItem) {
+        self.selected = Some(item.idx);
+        self.quantity = item.entry.quantity;
+        self.durability = item.durability.unwrap_or_default();
+    }
+
+    /// Logs why a write was refused.
+    fn log_refused(&mut self, what: &str) {
+        if self.inventory.check_layout().is_none() {
+            self.logs
+                .push(format!("Couldn't {what}: inventory layout not recognized, or not in game"));
+        } else {
+            self.logs.push(format!("Couldn't {what}"));
+        }
+    }
+
+    fn selected_item(&self) -> Option<InventoryItem> {
+        let selected = self.selected?;
+        self.items.iter().map(|(item, _)| *item).find(|item| item.idx == selected)
+    }
+
+    fn set_quantity(&mut self) {
+        let Some(item) = self.selected_item() else {
+            return;
+        };
+
+        match self.inventory.set_quantity(item.idx, self.quantity) {
+            Some(()) => self.logs.push(format!(
+                "Set {} quantity to {}",
+                item_name(item.entry.item_id),
+                self.quantity
+            )),
+            None => self.log_refused("set quantity"),
+        }
+        self.refresh();
+    }
+
+    fn set_durability(&mut self) {
+        let Some(item) = self.selected_item() else {
+            return;
+        };
+
+        match self.inventory.set_durability(item.idx, self.durability) {
+            Some(()) => self.logs.push(format!(
+                "Set {} durability to {}",
+                item_name(item.entry.item_id),
+                self.durability
+            )),
+            None => self.log_refused("set durability"),
+        }
+        self.refresh();
+    }
+
+    fn remove(&mut self) {
+        let Some(item) = self.selected_item() else {
+            return;
+        };
+
+        if item.slot.is_some() {
+            self.logs.push("Unequip the item before removing it".to_string());
+            return;
+        }
+
+        if !item.entry.is_goods() {
+            self.logs.push("Only goods can be removed".to_string());
+            return;
+        }
+
+        match self.inventory.remove(item.idx) {
+            Some(()) => self.logs.push(format!("Removed {}", item_name(item.entry.item_id))),
+            None => self.log_refused("remove item"),
+        }
+        self.selected = None;
+        self.refresh();
+    }
+
+    /// Spawns another copy of the selected entry, with the same durability
+    /// for weapons and armor.
+    fn duplicate(&mut self) {
+        let Some(item) = self.selected_item() else {
+            return;
+        };
+
+        let i = ItemSpawnInstance {
+            spawn_item_func_ptr: self.func_ptr as _,
+            map_item_man: self.map_item_man as _,
+            qty: item.entry.quantity,
+            durability: item.durability.unwrap_or(100),
+            item_id: item.entry.item_id,
+            infusion: 0,
+            upgrade: 0,
+        };
+
+        self.logs.push(format!("Duplicating {}", item_name(item.entry.item_id)));
+
+        unsafe {
+            i.spawn();
+        }
+        self.refresh();
+    }
+}
+
+impl Widget for InventoryEditor {
+    fn render(&mut self, ui: &imgui::Ui) {
+        if self.modal.open_button(ui) {
+            self.refresh();
+        }
+
+        if let Some(_token) = self.modal.begin(ui) {
+            let button_height = button_height(ui);
+            let filter = filter_input(ui, "##inventory-filter", &mut self.filter_string);
+            let mut clicked = None;
+
+            ui.child_window("##inventory-list").size([MODAL_WIDTH, 200.]).build(|| {
+                for (item, name) in &self.items {
+                    if !filter.is_empty() && !name.to_lowercase().contains(&filter) {
+                        continue;
+                    }
+
+                    let mut label = format!("{name} x{}", item.entry.quantity);
+                    if let Some(durability) = item.durability {
+                        label.push_str(&format!(" ({durability} dur.)"));
+                    }
+                    if let Some(slot) = item.slot {
+                        label.push_str(&format!(" [{}]", slot.label()));
+                    }
+                    label.push_str(&format!("##{}", item.idx));
+
+                    if ui.selectable_config(label).selected(self.selected == Some(item.idx)).build()
+                    {
+                        clicked = Some(*item);
+                    }
+                }
+            });
+
+            if let Some(item) = clicked {
+                self.select(item);
+            }
+
+            if let Some(item) = self.selected_item() {
+                ui.set_next_item_width(HALF_WIDTH);
+                ui.input_scalar("##inventory-quantity", &mut self.quantity).build();
+                ui.same_line();
+                if ui.button_with_size("Set quantity", [HALF_WIDTH, button_height]) {
+                    self.set_quantity();
+                }
+
+                if item.durability.is_some() {
+                    ui.set_next_item_width(HALF_WIDTH);
+                    ui.input_scalar("##inventory-durability", &mut self.durability).build();
+                    ui.same_line();
+                    if ui.button_with_size("Set durability", [HALF_WIDTH, button_height]) {
+                        self.set_durability();
+                    }
+                }
+
+                if ui.button_with_size("Duplicate", [HALF_WIDTH, button_height]) {
+                    self.duplicate();
+                }
+                ui.same_line();
+                if ui.button_with_size("Remove", [HALF_WIDTH, button_height]) {
+                    self.remove();
+                }
+            }
+
+            if ui.button_with_size("Refresh", [MODAL_WIDTH, button_height]) {
+                self.refresh();
+            }
+
+            self.modal.close_button(ui);
+        }
+    }
+
+    fn interact(&mut self, ui: &imgui::Ui) {
+        self.modal.interact(ui);
+    }
+
+    fn log(&mut self, tx: Sender<String>) {
+        for x in self.logs.drain(..) {
+            tx.send(x).ok();
+        }
+    }
+}
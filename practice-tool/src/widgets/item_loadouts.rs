@@ -0,0 +1,159 @@
+//! Named lists of items that the item spawner spawns in one go.
+//!
+//! Loadouts are stored in `jdsd_dsiii_practice_tool_loadouts.toml`, next to
+//! the practice tool's DLL:
+//!
+//! ```toml
+//! [[loadout]]
+//! name = "Any% route kit"
+//!
+//! [[loadout.items]]
+//! id = 0x000F4240
+//! infusion = "Sharp"
+//! upgrade = 10
+//!
+//! [[loadout.items]]
+//! id = 0x400001F4
+//! qty = 5
+//! ```
+
+use serde::{Deserialize, Serialize};
+
+use crate::widgets::item_spawn::INFUSION_TYPES;
+use crate::widgets::loadout_file::LoadoutFile;
+
+pub(crate) const LOADOUTS_FILE: &str = "jdsd_dsiii_practice_tool_loadouts.toml";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct LoadoutItem {
+    pub(crate) id: u32,
+    #[serde(default)]
+    pub(crate) infusion: Infusion,
+    #[serde(default)]
+    pub(crate) upgrade: u32,
+    #[serde(default = "LoadoutItem::default_qty")]
+    pub(crate) qty: u32,
+    #[serde(default = "LoadoutItem::default_durability")]
+    pub(crate) durability: u32,
+}
+
+impl LoadoutItem {
+    fn default_qty() -> u32 {
+        1
+    }
+
+    fn default_durability() -> u32 {
+        100
+    }
+}
+
+/// Index in [`INFUSION_TYPES`], written as the infusion's name.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub(crate) struct Infusion(pub(crate) usize);
+
+impl TryFrom<String> for Infusion {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        INFUSION_TYPES
+            .iter()
+            .position(|(_, name)| name.eq_ignore_ascii_case(&value))
+            .map(Infusion)
+            .ok_or_else(|| format!("Unrecognized infusion: {value}"))
+    }
+}
+
+impl From<Infusion> for String {
+    fn from(value: Infusion) -> Self {
+        INFUSION_TYPES[value.0].1.to_string()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct Loadout {
+    pub(crate) name: String,
+    #[serde(default)]
+    pub(crate) items: Vec<LoadoutItem>,
+}
+
+/// The item loadouts, in [`LOADOUTS_FILE`].
+pub(crate) type Loadouts = LoadoutFile<Loadout>;
+
+impl Loadouts {
+    /// Appends an item to the loadout called `name`, creating it if needed.
+    pub(crate) fn add_item(&mut self, name: &str, item: LoadoutItem) {
+        let loadouts = self.loadouts_mut();
+        match loadouts.iter_mut().find(|l| l.name == name) {
+            Some(loadout) => loadout.items.push(item),
+            None => loadouts.push(Loadout { name: name.to_string(), items: vec![item] }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let loadouts = Loadouts::parse(
+            r#"
+            [[loadout]]
+            name = "Any% route kit"
+
+            [[loadout.items]]
+            id = 0x000F4240
+            infusion = "sharp"
+            upgrade = 10
+
+            [[loadout.items]]
+            id = 0x400001F4
+            qty = 5
+
+            [[loadout]]
+            name = "Empty"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(loadouts.len(), 2);
+        assert_eq!(loadouts[0].items, vec![
+            LoadoutItem {
+                id: 0x000F4240,
+                infusion: Infusion(2),
+                upgrade: 10,
+                qty: 1,
+                durability: 100
+            },
+            LoadoutItem {
+                id: 0x400001F4,
+                infusion: Infusion(0),
+                upgrade: 0,
+                qty: 5,
+                durability: 100
+            },
+        ]);
+        assert!(loadouts[1].items.is_empty());
+
+        assert!(Loadouts::parse(
+            "[[loadout]]\nname = \"a\"\nitems = [{ id = 1, infusion = \"Wet\" }]"
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_add_and_serialize() {
+        let mut loadouts = Loadouts::default();
+        let item =
+            LoadoutItem { id: 1, infusion: Infusion(6), upgrade: 3, qty: 1, durability: 100 };
+        loadouts.add_item("Kit", item.clone());
+        loadouts.add_item("Kit", item.clone());
+        loadouts.add_item("Other", item);
+
+        let parsed = Loadouts::parse(&loadouts.serialize().unwrap()).unwrap();
+        assert_eq!(parsed, loadouts.loadouts());
+        assert_eq!(parsed[0].items.len(), 2);
+        assert_eq!(parsed[1].name, "Other");
+    }
+}
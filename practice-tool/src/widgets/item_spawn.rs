@@ -1,4 +1,5 @@
 use std::borrow::Cow;
+use std::collections::HashMap;
 use std::ffi::c_void;
 use std::fmt::Display;
 
@@ -8,6 +9,7 @@ use imgui::sys::{
 };
 use imgui::{Condition, InputText, TreeNodeFlags};
 use libds3::memedit::Bitflag;
+use libds3::prelude::*;
 use once_cell::sync::Lazy;
 use practice_tool_core::crossbeam_channel::Sender;
 use practice_tool_core::key::Key;
@@ -15,9 +17,11 @@ use practice_tool_core::widgets::{scaling_factor, Widget, BUTTON_HEIGHT, BUTTON_
 use serde::de::Visitor;
 use serde::{Deserialize, Deserializer};
 
+use crate::widgets::item_loadouts::{Infusion, LoadoutItem, Loadouts};
+
 const DEFAULT_ITEM: u32 = 0x007A1200;
 
-static INFUSION_TYPES: [(u32, &str); 16] = [
+pub(crate) static INFUSION_TYPES: [(u32, &str); 16] = [
     (0, "Normal"),
     (100, "Heavy"),
     (200, "Sharp"),
@@ -161,6 +165,53 @@ const ISP_TAG: &str = "##item-spawn";
 static ITEM_ID_TREE: Lazy<Vec<ItemIDNode>> =
     Lazy::new(|| serde_json::from_str(include_str!("item_ids.json")).unwrap());
 
+/// Names of the items in the item tree, by item id.
+static ITEM_NAMES: Lazy<HashMap<u32, &'static str>> = Lazy::new(|| {
+    fn collect(node: &'static ItemIDNode, names: &mut HashMap<u32, &'static str>) {
+        match node {
+            ItemIDNode::Leaf { id, desc } => {
+                names.insert(id.0, desc.as_str());
+            },
+            ItemIDNode::Node { children, .. } => {
+                children.iter().for_each(|child| collect(child, names))
+            },
+        }
+    }
+
+    let mut names = HashMap::new();
+    ITEM_ID_TREE.iter().for_each(|node| collect(node, &mut names));
+    names
+});
+
+/// Name of an item, from the item tree or else from the param names.
+/// Infused and upgraded weapons are named after their base weapon.
+pub(crate) fn item_name(item_id: u32) -> String {
+    if let Some(name) = ITEM_NAMES.get(&item_id) {
+        return name.to_string();
+    }
+
+    if item_id & ITEM_CATEGORY_MASK == ITEM_CATEGORY_WEAPON {
+        let base_id = item_id - item_id % 10000;
+        let infusion = item_id % 10000 - item_id % 100;
+        let upgrade = item_id % 100;
+
+        if let (Some(name), Some((_, infusion_name))) =
+            (ITEM_NAMES.get(&base_id), INFUSION_TYPES.iter().find(|(i, _)| *i == infusion))
+        {
+            return match infusion {
+                0 => format!("{name} +{upgrade}"),
+                _ => format!("{infusion_name} {name} +{upgrade}"),
+            };
+        }
+    }
+
+    item_param(item_id)
+        .and_then(|param| PARAM_NAMES.get(param)?.get(&((item_id & !ITEM_CATEGORY_MASK) as usize)))
+        .and_then(|name| name.split(" -- ").next())
+        .map(String::from)
+        .unwrap_or_else(|| format!("#{item_id:08X}"))
+}
+
 #[derive(Debug)]
 pub(crate) struct ItemSpawner<'a> {
     func_ptr: usize,
@@ -181,6 +232,10 @@ pub(crate) struct ItemSpawner<'a> {
     filter_string: String,
     logs: Vec<String>,
     item_id_tree: Vec<ItemIDNodeRef<'a>>,
+
+    loadouts: Loadouts,
+    loadout_idx: usize,
+    loadout_name: String,
 }
 
 impl ItemSpawner<'_> {
@@ -190,6 +245,7 @@ impl ItemSpawner<'_> {
         sentinel: Bitflag<u8>,
         hotkey_load: Option<Key>,
         hotkey_close: Key,
+        loadouts: Loadouts,
     ) -> Self {
         let label_load = if let Some(hotkey_load) = hotkey_load {
             format!("Spawn item ({hotkey_load})")
@@ -213,6 +269,9 @@ impl ItemSpawner<'_> {
             filter_string: String::new(),
             logs: Vec::new(),
             item_id_tree: ITEM_ID_TREE.iter().map(ItemIDNodeRef::from).collect(),
+            loadouts,
+            loadout_idx: 0,
+            loadout_name: String::new(),
         }
     }
 
@@ -222,22 +281,44 @@ impl ItemSpawner<'_> {
             return;
         }
 
-        let upgrade = UPGRADES[self.upgrade].0;
-        let infusion = INFUSION_TYPES[self.infusion_type].0;
+        self.spawn_item(&self.current_item());
+    }
+
+    fn spawn_loadout(&mut self) {
+        if self.sentinel.get().is_none() {
+            self.write_log("Not spawning loadout when not in game".into());
+            return;
+        }
+
+        let Some(loadout) = self.loadouts.loadouts().get(self.loadout_idx).cloned() else {
+            return;
+        };
+
+        self.write_log(format!("Spawning loadout {}", loadout.name));
+        for item in &loadout.items {
+            self.spawn_item(item);
+        }
+    }
+
+    fn spawn_item(&mut self, item: &LoadoutItem) {
+        if let Err(e) = validate_item(&PARAMS.read(), item) {
+            self.write_log(format!("Not spawning: {e}"));
+            return;
+        }
 
         let i = ItemSpawnInstance {
             spawn_item_func_ptr: self.func_ptr as _,
             map_item_man: self.map_item_man as _,
-            qty: self.qty,
-            durability: self.durability,
-            upgrade,
-            infusion,
-            item_id: self.item_id,
+            qty: item.qty,
+            durability: item.durability,
+            upgrade: item.upgrade,
+            infusion: INFUSION_TYPES[item.infusion.0].0,
+            item_id: item.id,
         };
 
         self.write_log(format!(
-            "Spawning {} #{} {} {}",
-            i.qty, self.item_id, UPGRADES[self.upgrade].1, INFUSION_TYPES[self.infusion_type].1,
+            "Spawning {} #{} +{} {}",
+            i.qty, item.id, item.upgrade, INFUSION_TYPES[item.infusion.0].1,
         ));
 
         unsafe {
@@ -245,6 +326,36 @@ impl ItemSpawner<'_> {
         }
     }
 
+    /// The item selected in the UI.
+    fn current_item(&self) -> LoadoutItem {
+        LoadoutItem {
+            id: self.item_id,
+            infusion: Infusion(self.infusion_type),
+            upgrade: UPGRADES[self.upgrade].0,
+            qty: self.qty,
+            durability: self.durability,
+        }
+    }
+
+    /// Appends the item selected in the UI to the loadout named in the UI,
+    /// and saves the loadouts file.
+    fn add_to_loadout(&mut self) {
+        let name = self.loadout_name.trim().to_string();
+        if name.is_empty() {
+            self.write_log("Enter a loadout name first".into());
+            return;
+        }
+
+        self.loadouts.add_item(&name, self.current_item());
+        self.loadout_idx =
+            self.loadouts.loadouts().iter().position(|l| l.name == name).unwrap_or_default();
+
+        match self.loadouts.save() {
+            Ok(()) => self.write_log(format!("Added #{} to loadout {name}", self.item_id)),
+            Err(e) => self.write_log(e),
+        }
+    }
+
     fn write_log(&mut self, log: String) {
         self.logs.push(log);
     }
@@ -300,19 +411,51 @@ impl Widget for ItemSpawner<'_> {
                 }
             });
 
+            // Only offer the infusions and upgrades that exist for the item.
+            let max_upgrades = {
+                let params = PARAMS.read();
+                INFUSION_TYPES
+                    .iter()
+                    .map(|&(infusion, _)| max_upgrade(&params, self.item_id, infusion))
+                    .collect::<Vec<_>>()
+            };
+
+            if max_upgrades[self.infusion_type].is_none() {
+                self.infusion_type = 0;
+            }
+            let max_upgrade = max_upgrades[self.infusion_type].unwrap_or(0);
+            self.upgrade = self.upgrade.min(max_upgrade as usize);
+
             ui.set_next_item_width(195.);
-            ui.combo(
-                "##item-spawn-infusion-type",
-                &mut self.infusion_type,
-                &INFUSION_TYPES,
-                |(_, label)| Cow::Borrowed(label),
-            );
+            if let Some(_token) =
+                ui.begin_combo("##item-spawn-infusion-type", INFUSION_TYPES[self.infusion_type].1)
+            {
+                for (idx, (_, label)) in INFUSION_TYPES.iter().enumerate() {
+                    if ui
+                        .selectable_config(label)
+                        .selected(idx == self.infusion_type)
+                        .disabled(max_upgrades[idx].is_none())
+                        .build()
+                    {
+                        self.infusion_type = idx;
+                    }
+                }
+            }
 
             ui.same_line();
             ui.set_next_item_width(195.);
-            ui.combo("##item-spawn-upgrade", &mut self.upgrade, &UPGRADES, |(_, label)| {
-                Cow::Borrowed(label)
-            });
+            if let Some(_token) = ui.begin_combo("##item-spawn-upgrade", UPGRADES[self.upgrade].1) {
+                for (idx, &(upgrade, label)) in UPGRADES.iter().enumerate() {
+                    if ui
+                        .selectable_config(label)
+                        .selected(idx == self.upgrade)
+                        .disabled(upgrade > max_upgrade)
+                        .build()
+                    {
+                        self.upgrade = idx;
+                    }
+                }
+            }
 
             ui.slider_config("Qty", 1, 99).build(&mut self.qty);
             ui.slider_config("Dur", 0, 9999).build(&mut self.durability);
@@ -320,6 +463,33 @@ impl Widget for ItemSpawner<'_> {
                 self.spawn();
             }
 
+            ui.separator();
+
+            if !self.loadouts.loadouts().is_empty() {
+                ui.set_next_item_width(400.);
+                ui.combo(
+                    "##item-spawn-loadout",
+                    &mut self.loadout_idx,
+                    self.loadouts.loadouts(),
+                    |loadout| Cow::Borrowed(loadout.name.as_str()),
+                );
+
+                if ui.button_with_size("Spawn loadout", [400., button_height]) {
+                    self.spawn_loadout();
+                }
+            }
+
+            ui.set_next_item_width(195.);
+            InputText::new(ui, "##item-spawn-loadout-name", &mut self.loadout_name)
+                .hint("Loadout name...")
+                .build();
+            ui.same_line();
+            if ui.button_with_size("Add to loadout", [195., button_height]) {
+                self.add_to_loadout();
+            }
+
+            ui.separator();
+
             if ui.button_with_size("Clear", [400., button_height]) {
                 self.filter_string.clear();
                 self.qty = 1;
@@ -352,6 +522,69 @@ impl Widget for ItemSpawner<'_> {
     }
 }
 
+const ITEM_CATEGORY_MASK: u32 = 0xF000_0000;
+const ITEM_CATEGORY_WEAPON: u32 = 0x0000_0000;
+const ITEM_CATEGORY_PROTECTOR: u32 = 0x1000_0000;
+
+/// Param holding the row of an item, from the category in its top 4 bits.
+fn item_param(item_id: u32) -> Option<&'static str> {
+    match item_id & ITEM_CATEGORY_MASK {
+        ITEM_CATEGORY_WEAPON => Some("EquipParamWeapon"),
+        ITEM_CATEGORY_PROTECTOR => Some("EquipParamProtector"),
+        0x2000_0000 => Some("EquipParamAccessory"),
+        0x4000_0000 => Some("EquipParamGoods"),
+        _ => None,
+    }
+}
+
+/// Highest upgrade of an item with the given infusion, or `None` if the game
+/// has no such item. Weapons are looked up with their infusion in
+/// `EquipParamWeapon`, and their upgrades in `ReinforceParamWeapon`; other
+/// items can't be infused nor upgraded.
+fn max_upgrade(params: &Params, item_id: u32, infusion: u32) -> Option<u32> {
+    match item_id & ITEM_CATEGORY_MASK {
+        ITEM_CATEGORY_WEAPON => {
+            let weapon =
+                unsafe { params.get_equip_param_weapon_by_id((item_id + infusion) as u64) }?
+                    .param?;
+            let reinforce_type_id = u64::try_from(weapon.reinforce_type_id).ok();
+
+            Some(
+                UPGRADES
+                    .iter()
+                    .map(|&(upgrade, _)| upgrade)
+                    .take_while(|&upgrade| {
+                        reinforce_type_id
+                            .and_then(|id| {
+                                params.index_of("ReinforceParamWeapon", id + upgrade as u64)
+                            })
+                            .is_some()
+                    })
+                    .last()
+                    .unwrap_or(0),
+            )
+        },
+        ITEM_CATEGORY_PROTECTOR => {
+            let row_id = (item_id & !ITEM_CATEGORY_MASK) as u64;
+            (infusion == 0 && params.index_of("EquipParamProtector", row_id).is_some()).then_some(0)
+        },
+        _ => (infusion == 0).then_some(0),
+    }
+}
+
+/// Refuses items that don't exist in the game, as they can corrupt the save.
+fn validate_item(params: &Params, item: &LoadoutItem) -> Result<(), String> {
+    let (infusion, infusion_name) = INFUSION_TYPES[item.infusion.0];
+
+    match max_upgrade(params, item.id, infusion) {
+        None => Err(format!("{infusion_name} #{} doesn't exist", item.id)),
+        Some(max) if item.upgrade > max => {
+            Err(format!("{infusion_name} #{} can't be upgraded past +{max}", item.id))
+        },
+        Some(_) => Ok(()),
+    }
+}
+
 #[derive(Debug)]
 struct HexU32(u32);
 
@@ -395,14 +628,14 @@ impl<'de> Deserialize<'de> for HexU32 {
 }
 
 #[derive(Debug)]
-struct ItemSpawnInstance {
-    spawn_item_func_ptr: u64,
-    map_item_man: u64,
-    qty: u32,
-    durability: u32,
-    item_id: u32,
-    infusion: u32,
-    upgrade: u32,
+pub(crate) struct ItemSpawnInstance {
+    pub(crate) spawn_item_func_ptr: u64,
+    pub(crate) map_item_man: u64,
+    pub(crate) qty: u32,
+    pub(crate) durability: u32,
+    pub(crate) item_id: u32,
+    pub(crate) infusion: u32,
+    pub(crate) upgrade: u32,
 }
 
 impl Display for ItemSpawnInstance {
@@ -416,7 +649,7 @@ impl Display for ItemSpawnInstance {
 }
 
 impl ItemSpawnInstance {
-    unsafe fn spawn(&self) {
+    pub(crate) unsafe fn spawn(&self) {
         #[repr(C)]
         struct SpawnRequest {
             unknown: u32,
@@ -438,3 +671,47 @@ impl ItemSpawnInstance {
         spawn_fn_ptr(*pp_map_item_man, &mut spawn_request as *mut _, &mut [0u32; 4] as *mut _);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+
+    #[test]
+    fn test_item_ids_exist() {
+        assert!(!ITEM_NAMES.is_empty());
+
+        let mut missing = ITEM_NAMES
+            .keys()
+            .filter(|&&id| {
+                let row_id = (id & !ITEM_CATEGORY_MASK) as usize;
+                item_param(id)
+                    .and_then(|param| PARAM_NAMES.get(param))
+                    .map(|names| !names.contains_key(&row_id))
+                    .unwrap_or(true)
+            })
+            .map(|id| format!("{id:08X}"))
+            .collect::<Vec<_>>();
+        missing.sort();
+
+        assert!(missing.is_empty(), "Items without a param row: {missing:?}");
+
+        let names = ITEM_NAMES.values().collect::<HashSet<_>>();
+        let dropped = include_str!("../../../xtask/src/codegen/item_list/known_items.txt")
+            .lines()
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .filter(|name| !names.contains(name))
+            .collect::<Vec<_>>();
+
+        assert!(dropped.is_empty(), "Known items missing from the item tree: {dropped:?}");
+    }
+
+    #[test]
+    fn test_item_name() {
+        assert_eq!(item_name(0x000F4240), "Dagger");
+        assert_eq!(item_name(0x000F4240 + 200 + 10), "Sharp Dagger +10");
+        assert_eq!(item_name(0x000F4240 + 3), "Dagger +3");
+        assert_eq!(item_name(0x8FFF_FFFF), "#8FFFFFFF");
+    }
+}
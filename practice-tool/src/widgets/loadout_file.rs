@@ -0,0 +1,83 @@
+//! Named loadouts stored next to the practice tool's DLL, as the
+//! `[[loadout]]` tables of a TOML file.
+
+use std::path::{Path, PathBuf};
+
+use hudhook::tracing::error;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::util;
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Content<T> {
+    #[serde(default = "Vec::new")]
+    loadout: Vec<T>,
+}
+
+#[derive(Debug)]
+pub(crate) struct LoadoutFile<T> {
+    /// Where to save the loadouts. `None` if the file couldn't be read, so
+    /// that it isn't overwritten.
+    path: Option<PathBuf>,
+    loadouts: Vec<T>,
+}
+
+impl<T> Default for LoadoutFile<T> {
+    fn default() -> Self {
+        LoadoutFile { path: None, loadouts: Vec::new() }
+    }
+}
+
+impl<T: Clone + Serialize + DeserializeOwned> LoadoutFile<T> {
+    /// Reads the file called `file_name` next to the DLL. Errors are logged,
+    /// and yield no loadouts.
+    pub(crate) fn load_default(file_name: &str) -> LoadoutFile<T> {
+        let Some(path) = util::get_dll_path().map(|path| path.with_file_name(file_name)) else {
+            error!("Couldn't find {file_name}");
+            return LoadoutFile::default();
+        };
+
+        LoadoutFile::load(&path).unwrap_or_else(|e| {
+            error!("{e}");
+            LoadoutFile::default()
+        })
+    }
+
+    /// Reads a loadouts file. A missing file yields no loadouts.
+    pub(crate) fn load(path: &Path) -> Result<LoadoutFile<T>, String> {
+        let loadouts = if path.exists() {
+            let content = std::fs::read_to_string(path)
+                .map_err(|e| format!("Couldn't read {}: {e}", path.display()))?;
+            LoadoutFile::parse(&content)
+                .map_err(|e| format!("Couldn't parse {}: {e}", path.display()))?
+        } else {
+            Vec::new()
+        };
+
+        Ok(LoadoutFile { path: Some(path.to_path_buf()), loadouts })
+    }
+
+    pub(crate) fn parse(content: &str) -> Result<Vec<T>, String> {
+        toml::from_str::<Content<T>>(content).map(|c| c.loadout).map_err(|e| e.to_string())
+    }
+
+    pub(crate) fn serialize(&self) -> Result<String, String> {
+        toml::to_string(&Content { loadout: self.loadouts.clone() })
+            .map_err(|e| format!("Couldn't serialize loadouts: {e}"))
+    }
+
+    pub(crate) fn save(&self) -> Result<(), String> {
+        let path = self.path.as_ref().ok_or("Loadouts file wasn't loaded, not saving")?;
+        std::fs::write(path, self.serialize()?)
+            .map_err(|e| format!("Couldn't write {}: {e}", path.display()))
+    }
+
+    pub(crate) fn loadouts(&self) -> &[T] {
+        &self.loadouts
+    }
+
+    pub(crate) fn loadouts_mut(&mut self) -> &mut Vec<T> {
+        &mut self.loadouts
+    }
+}
@@ -1,8 +1,17 @@
+pub(crate) mod bonfires;
+pub(crate) mod camera;
 pub(crate) mod character_stats;
+pub(crate) mod covenant;
 pub(crate) mod cycle_speed;
+pub(crate) mod equipment;
 pub(crate) mod flag;
+pub(crate) mod free_camera;
 pub(crate) mod group;
+pub(crate) mod inventory;
+pub(crate) mod item_loadouts;
 pub(crate) mod item_spawn;
+pub(crate) mod loadout_file;
+pub(crate) mod modal;
 pub(crate) mod nudge_pos;
 pub(crate) mod open_menu;
 pub(crate) mod position;
@@ -10,3 +19,4 @@ pub(crate) mod quitout;
 pub(crate) mod savefile_manager;
 pub(crate) mod souls;
 pub(crate) mod target;
+pub(crate) mod warp;
@@ -0,0 +1,118 @@
+use imgui::sys::{igGetCursorPosX, igGetCursorPosY, igGetWindowPos, igSetNextWindowPos, ImVec2};
+use imgui::{Condition, InputText, PopupToken};
+use practice_tool_core::key::Key;
+use practice_tool_core::widgets::{scaling_factor, BUTTON_HEIGHT, BUTTON_WIDTH};
+
+/// Width of the modals' contents.
+pub(crate) const MODAL_WIDTH: f32 = 400.;
+/// Width of two buttons sharing a line in a modal.
+pub(crate) const HALF_WIDTH: f32 = 195.;
+
+/// A button opening a modal to its right, with a hotkey to open it and one
+/// to close it. The widget owning it renders the modal's contents:
+///
+/// ```ignore
+/// if self.modal.open_button(ui) {
+///     self.refresh();
+/// }
+/// if let Some(_token) = self.modal.begin(ui) {
+///     // ...
+///     self.modal.close_button(ui);
+/// }
+/// ```
+#[derive(Debug)]
+pub(crate) struct Modal {
+    tag: &'static str,
+    hotkey_open: Option<Key>,
+    hotkey_close: Key,
+
+    label_open: String,
+    label_close: String,
+    open_requested: bool,
+}
+
+impl Modal {
+    pub(crate) fn new(
+        name: &str,
+        tag: &'static str,
+        hotkey_open: Option<Key>,
+        hotkey_close: Key,
+    ) -> Self {
+        let label_open = match hotkey_open {
+            Some(k) => format!("{name} ({k})"),
+            None => name.to_string(),
+        };
+        let label_close = format!("Close ({hotkey_close})");
+
+        Modal { tag, hotkey_open, hotkey_close, label_open, label_close, open_requested: false }
+    }
+
+    /// Renders the button opening the modal. Returns `true` when the modal
+    /// is opened, from the button or the hotkey.
+    pub(crate) fn open_button(&mut self, ui: &imgui::Ui) -> bool {
+        let scale = scaling_factor(ui);
+
+        let (x, y) = unsafe {
+            let mut wnd_pos = ImVec2::default();
+            igGetWindowPos(&mut wnd_pos);
+            (igGetCursorPosX() + wnd_pos.x, igGetCursorPosY() + wnd_pos.y)
+        };
+
+        let opened = ui.button_with_size(&self.label_open, [BUTTON_WIDTH * scale, BUTTON_HEIGHT])
+            || std::mem::take(&mut self.open_requested);
+        if opened {
+            ui.open_popup(self.tag);
+        }
+
+        unsafe {
+            igSetNextWindowPos(
+                ImVec2::new(x + 200. * scale, y),
+                Condition::Always as i8 as _,
+                ImVec2::new(0., 0.),
+            )
+        };
+
+        opened
+    }
+
+    /// Begins the modal. Its contents go where the token is alive.
+    pub(crate) fn begin<'ui>(&self, ui: &'ui imgui::Ui) -> Option<PopupToken<'ui>> {
+        ui.modal_popup_config(self.tag)
+            .resizable(false)
+            .movable(false)
+            .title_bar(false)
+            .scroll_bar(false)
+            .begin_popup()
+    }
+
+    /// Renders the button closing the modal, and closes it on the hotkey
+    /// unless a text input is being edited.
+    pub(crate) fn close_button(&self, ui: &imgui::Ui) {
+        if ui.button_with_size(&self.label_close, [MODAL_WIDTH, button_height(ui)])
+            || (self.hotkey_close.is_pressed(ui)
+                && !(ui.io().want_capture_keyboard && ui.is_any_item_active()))
+        {
+            ui.close_current_popup();
+        }
+    }
+
+    /// Opens the modal at the next render if its hotkey is pressed.
+    pub(crate) fn interact(&mut self, ui: &imgui::Ui) {
+        if self.hotkey_open.map(|k| k.is_pressed(ui)).unwrap_or(false) {
+            self.open_requested = true;
+        }
+    }
+}
+
+/// Height of the buttons in a modal.
+pub(crate) fn button_height(ui: &imgui::Ui) -> f32 {
+    BUTTON_HEIGHT * scaling_factor(ui)
+}
+
+/// Renders a filter input spanning the modal. Returns the filter in lower
+/// case.
+pub(crate) fn filter_input(ui: &imgui::Ui, id: &str, filter: &mut String) -> String {
+    let _tok = ui.push_item_width(-1.);
+    InputText::new(ui, id, filter).hint("Filter...").build();
+    filter.to_lowercase()
+}
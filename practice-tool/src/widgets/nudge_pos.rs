@@ -1,15 +1,126 @@
 use libds3::prelude::*;
+use practice_tool_core::crossbeam_channel::Sender;
 use practice_tool_core::key::Key;
 use practice_tool_core::widgets::nudge_position::NudgePosition;
-use practice_tool_core::widgets::Widget;
+use practice_tool_core::widgets::{scaling_factor, Widget, BUTTON_HEIGHT, BUTTON_WIDTH};
 
 use crate::widgets::position::SavePosition;
 
+/// Horizontal `[x, z]` offset moving `forward` along the direction of
+/// `angle`, in radians, and `right` perpendicular to it.
+pub(crate) fn facing_offset(angle: f32, forward: f32, right: f32) -> [f32; 2] {
+    let (sin, cos) = angle.sin_cos();
+    [forward * sin + right * cos, forward * cos - right * sin]
+}
+
+/// Moves the player by `forward` and `right` relative to where they're
+/// facing.
+fn nudge_facing(
+    (ptr_angle, ptr_pos): &(PointerChain<f32>, PointerChain<[f32; 3]>),
+    forward: f32,
+    right: f32,
+) {
+    if let (Some([x, y, z]), Some(angle)) = (ptr_pos.read(), ptr_angle.read()) {
+        let [dx, dz] = facing_offset(angle, forward, right);
+        ptr_pos.write([x + dx, y, z + dz]);
+    }
+}
+
 pub(crate) fn nudge_position(
     ptr: (PointerChain<f32>, PointerChain<[f32; 3]>),
+    ptr_map: PointerChain<u32>,
     nudge: f32,
     key_nudge_up: Option<Key>,
     key_nudge_down: Option<Key>,
 ) -> Box<dyn Widget> {
-    Box::new(NudgePosition::new(SavePosition::new(ptr, nudge), key_nudge_up, key_nudge_down))
+    Box::new(NudgePosition::new(
+        SavePosition::new(ptr, ptr_map, nudge),
+        key_nudge_up,
+        key_nudge_down,
+    ))
+}
+
+/// Directions of the facing nudges, as forward and right components.
+const FACING_DIRECTIONS: [(&str, [f32; 2]); 4] =
+    [("Forward", [1., 0.]), ("Back", [-1., 0.]), ("Left", [0., -1.]), ("Right", [0., 1.])];
+
+/// Nudges the player forward, back, left or right relative to where they're
+/// facing.
+pub(crate) struct NudgeFacing {
+    ptr: (PointerChain<f32>, PointerChain<[f32; 3]>),
+    /// Step along the facing direction.
+    step_forward: f32,
+    /// Step perpendicular to the facing direction.
+    step_sideways: f32,
+    hotkeys: [Option<Key>; 4],
+    labels: [String; 4],
+}
+
+impl NudgeFacing {
+    pub(crate) fn new(
+        ptr: (PointerChain<f32>, PointerChain<[f32; 3]>),
+        step_forward: f32,
+        step_sideways: f32,
+        hotkeys: [Option<Key>; 4],
+    ) -> Self {
+        let labels = std::array::from_fn(|i| {
+            let (name, _) = FACING_DIRECTIONS[i];
+            match hotkeys[i] {
+                Some(k) => format!("{name} ({k})"),
+                None => name.to_string(),
+            }
+        });
+
+        NudgeFacing { ptr, step_forward, step_sideways, hotkeys, labels }
+    }
+
+    fn nudge(&mut self, idx: usize) {
+        let (_, [forward, right]) = FACING_DIRECTIONS[idx];
+        nudge_facing(&self.ptr, forward * self.step_forward, right * self.step_sideways);
+    }
+}
+
+impl Widget for NudgeFacing {
+    fn render(&mut self, ui: &imgui::Ui) {
+        let scale = scaling_factor(ui);
+        let spacing = ui.clone_style().item_spacing[0];
+        let button_width = (BUTTON_WIDTH * scale - spacing) / 2.;
+
+        for idx in 0..FACING_DIRECTIONS.len() {
+            if idx % 2 == 1 {
+                ui.same_line();
+            }
+            if ui.button_with_size(&self.labels[idx], [button_width, BUTTON_HEIGHT]) {
+                self.nudge(idx);
+            }
+        }
+    }
+
+    fn interact(&mut self, ui: &imgui::Ui) {
+        for idx in 0..FACING_DIRECTIONS.len() {
+            if self.hotkeys[idx].map(|k| k.is_pressed(ui)).unwrap_or(false) {
+                self.nudge(idx);
+            }
+        }
+    }
+
+    fn log(&mut self, _: Sender<String>) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close([x0, z0]: [f32; 2], [x1, z1]: [f32; 2]) {
+        assert!((x0 - x1).abs() < 1e-5 && (z0 - z1).abs() < 1e-5, "{x0},{z0} != {x1},{z1}");
+    }
+
+    #[test]
+    fn test_facing_offset() {
+        assert_close(facing_offset(0., 1., 0.), [0., 1.]);
+        assert_close(facing_offset(0., 0., 1.), [1., 0.]);
+        assert_close(facing_offset(std::f32::consts::FRAC_PI_2, 2., 0.), [2., 0.]);
+        assert_close(facing_offset(std::f32::consts::FRAC_PI_2, 0., 1.), [0., -1.]);
+        assert_close(facing_offset(std::f32::consts::PI, -1., 0.), [0., 1.]);
+    }
 }
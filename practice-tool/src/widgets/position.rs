@@ -1,15 +1,38 @@
 use std::fmt::Write;
+use std::sync::Mutex;
 
 use libds3::memedit::PointerChain;
+use libds3::prelude::MapId;
+use practice_tool_core::crossbeam_channel::{self, Receiver, Sender};
 use practice_tool_core::key::Key;
 use practice_tool_core::widgets::nudge_position::NudgePositionStorage;
 use practice_tool_core::widgets::position::{Position, PositionStorage};
 use practice_tool_core::widgets::Widget;
 
+/// A position saved by a slot, and the map it was saved in.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct SavedPosition {
+    pub(crate) position: [f32; 3],
+    pub(crate) map: Option<MapId>,
+}
+
+/// The position saved by each slot, in the order of the configuration, for
+/// the distance indicator.
+pub(crate) static SAVED_POSITIONS: Mutex<Vec<Option<SavedPosition>>> = Mutex::new(Vec::new());
+
 pub(super) struct SavePosition {
     ptr_angle: PointerChain<f32>,
     ptr_pos: PointerChain<[f32; 3]>,
+    ptr_map: PointerChain<u32>,
     saved_position: [f32; 4],
+    /// Map the position was saved in. World coordinates are only meaningful
+    /// within the same map block.
+    saved_map: Option<MapId>,
+    /// Index in [`SAVED_POSITIONS`], for the position slots.
+    slot: Option<usize>,
+    /// Where to report refused loads and unknown maps, for the position
+    /// slots.
+    logs: Option<Sender<String>>,
     label_current: String,
     label_stored: String,
     valid: bool,
@@ -17,11 +40,19 @@ pub(super) struct SavePosition {
 }
 
 impl SavePosition {
-    pub(super) fn new(ptr: (PointerChain<f32>, PointerChain<[f32; 3]>), nudge: f32) -> Self {
+    pub(super) fn new(
+        ptr: (PointerChain<f32>, PointerChain<[f32; 3]>),
+        ptr_map: PointerChain<u32>,
+        nudge: f32,
+    ) -> Self {
         Self {
             ptr_angle: ptr.0,
             ptr_pos: ptr.1,
+            ptr_map,
             saved_position: [0.0; 4],
+            saved_map: None,
+            slot: None,
+            logs: None,
             label_current: String::new(),
             label_stored: String::new(),
             valid: false,
@@ -34,13 +65,40 @@ impl PositionStorage for SavePosition {
     fn save(&mut self) {
         if let (Some(pos), Some(angle)) = (self.ptr_pos.read(), self.ptr_angle.read()) {
             self.saved_position = [pos[0], pos[1], pos[2], angle];
+            self.saved_map = self.ptr_map.read().map(MapId);
             self.valid = true;
+            if let (Some(slot), Ok(mut saved)) = (self.slot, SAVED_POSITIONS.lock()) {
+                saved[slot] = Some(SavedPosition { position: pos, map: self.saved_map });
+            }
         } else {
             self.valid = false;
         }
     }
 
     fn load(&mut self) {
+        // Coordinates are only comparable in the same map block, so loading
+        // is refused when both maps are known and differ. The map id offset
+        // isn't verified on every game version, so an unknown map only warns.
+        let (refused, message) = match (self.saved_map, self.ptr_map.read().map(MapId)) {
+            (Some(saved), Some(current)) if saved.same_block(&current) => (false, None),
+            (Some(saved), Some(current)) => {
+                (true, Some(format!("Not loading a position saved in {saved} while in {current}")))
+            },
+            (None, _) => {
+                (false, Some("Position saved in an unknown map, loading anyway".to_string()))
+            },
+            (_, None) => {
+                (false, Some("Current map unknown, loading the position anyway".to_string()))
+            },
+        };
+
+        if let (Some(message), Some(logs)) = (message, self.logs.as_ref()) {
+            logs.send(message).ok();
+        }
+        if refused {
+            return;
+        }
+
         self.ptr_pos.write([
             self.saved_position[0],
             self.saved_position[1],
@@ -79,6 +137,14 @@ impl PositionStorage for SavePosition {
         let [x, y, z, a] = self.saved_position;
 
         write!(self.label_stored, "{:7.1} {:7.1} {:7.1} {:7.1}", x, y, z, a).ok();
+        if let Some(map) = self.saved_map {
+            let current_map = self.ptr_map.read().map(MapId);
+            if current_map.is_some_and(|current| !map.same_block(&current)) {
+                write!(self.label_stored, " {map} (other map)").ok();
+            } else {
+                write!(self.label_stored, " {map}").ok();
+            }
+        }
 
         &self.label_stored
     }
@@ -102,10 +168,43 @@ impl NudgePositionStorage for SavePosition {
     }
 }
 
+/// A position slot, with its refused loads and unknown maps in the log.
+struct PositionSlot {
+    position: Position<SavePosition>,
+    logs: Receiver<String>,
+}
+
+impl Widget for PositionSlot {
+    fn render(&mut self, ui: &imgui::Ui) {
+        self.position.render(ui);
+    }
+
+    fn interact(&mut self, ui: &imgui::Ui) {
+        self.position.interact(ui);
+    }
+
+    fn log(&mut self, tx: Sender<String>) {
+        for x in self.logs.try_iter() {
+            tx.send(x).ok();
+        }
+        self.position.log(tx);
+    }
+}
+
 pub(crate) fn save_position(
     ptr: (PointerChain<f32>, PointerChain<[f32; 3]>),
+    ptr_map: PointerChain<u32>,
     key_load: Option<Key>,
     key_save: Option<Key>,
 ) -> Box<dyn Widget> {
-    Box::new(Position::new(SavePosition::new(ptr, 0.0), key_load, key_save))
+    let (tx, rx) = crossbeam_channel::unbounded();
+
+    let mut position = SavePosition::new(ptr, ptr_map, 0.0);
+    position.logs = Some(tx);
+    if let Ok(mut saved) = SAVED_POSITIONS.lock() {
+        position.slot = Some(saved.len());
+        saved.push(None);
+    }
+
+    Box::new(PositionSlot { position: Position::new(position, key_load, key_save), logs: rx })
 }
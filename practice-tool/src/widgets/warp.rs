@@ -0,0 +1,144 @@
+use libds3::prelude::*;
+use practice_tool_core::crossbeam_channel::Sender;
+use practice_tool_core::key::Key;
+use practice_tool_core::widgets::{scaling_factor, Widget, BUTTON_HEIGHT, BUTTON_WIDTH};
+
+use crate::widgets::modal::{button_height, filter_input, Modal, MODAL_WIDTH};
+
+const WARP_TAG: &str = "##bonfire-warp";
+
+/// Warps to the bonfire with row id `id`, logging the outcome.
+fn warp_to(warp: Option<&BonfireWarp>, id: u64, logs: &mut Vec<String>) {
+    let Some(warp) = warp else {
+        logs.push("Bonfire warp isn't supported on this game version".to_string());
+        return;
+    };
+
+    let Some(warp_event_id) = bonfire_warp_event(&PARAMS.read(), id) else {
+        logs.push(format!("No bonfire with id {id}"));
+        return;
+    };
+
+    match warp.warp(warp_event_id) {
+        Some(()) => logs.push(format!("Warping to {}", bonfire_name(id))),
+        None => logs.push("Couldn't warp".to_string()),
+    }
+}
+
+/// Searchable list of every bonfire.
+#[derive(Debug)]
+pub(crate) struct BonfireWarpMenu {
+    warp: Option<BonfireWarp>,
+    modal: Modal,
+
+    bonfires: Vec<Bonfire>,
+    selected: Option<u64>,
+    filter_string: String,
+    logs: Vec<String>,
+}
+
+impl BonfireWarpMenu {
+    pub(crate) fn new(hotkey_open: Option<Key>, hotkey_close: Key) -> Self {
+        BonfireWarpMenu {
+            warp: BonfireWarp::new(),
+            modal: Modal::new("Bonfire warp", WARP_TAG, hotkey_open, hotkey_close),
+            bonfires: Vec::new(),
+            selected: None,
+            filter_string: String::new(),
+            logs: Vec::new(),
+        }
+    }
+}
+
+impl Widget for BonfireWarpMenu {
+    fn render(&mut self, ui: &imgui::Ui) {
+        if self.modal.open_button(ui) {
+            self.bonfires = bonfires(&PARAMS.read());
+        }
+
+        if let Some(_token) = self.modal.begin(ui) {
+            let button_height = button_height(ui);
+            let filter = filter_input(ui, "##bonfire-warp-filter", &mut self.filter_string);
+
+            ui.child_window("##bonfire-warp-list").size([MODAL_WIDTH, 200.]).build(|| {
+                for bonfire in &self.bonfires {
+                    if !filter.is_empty() && !bonfire.name.to_lowercase().contains(&filter) {
+                        continue;
+                    }
+
+                    let label = format!("{} ({})##{}", bonfire.name, bonfire.id, bonfire.id);
+                    if ui
+                        .selectable_config(label)
+                        .selected(self.selected == Some(bonfire.id))
+                        .build()
+                    {
+                        self.selected = Some(bonfire.id);
+                    }
+                }
+            });
+
+            if let Some(id) = self.selected {
+                if ui.button_with_size("Warp", [MODAL_WIDTH, button_height]) {
+                    warp_to(self.warp.as_ref(), id, &mut self.logs);
+                    ui.close_current_popup();
+                }
+            }
+
+            self.modal.close_button(ui);
+        }
+    }
+
+    fn interact(&mut self, ui: &imgui::Ui) {
+        self.modal.interact(ui);
+    }
+
+    fn log(&mut self, tx: Sender<String>) {
+        for x in self.logs.drain(..) {
+            tx.send(x).ok();
+        }
+    }
+}
+
+/// Warps to a single bonfire, by its `BonfireWarpParam` row id.
+#[derive(Debug)]
+pub(crate) struct WarpToBonfire {
+    warp: Option<BonfireWarp>,
+    id: u64,
+    hotkey: Option<Key>,
+    label: String,
+    logs: Vec<String>,
+}
+
+impl WarpToBonfire {
+    pub(crate) fn new(id: u64, hotkey: Option<Key>) -> Self {
+        let name = bonfire_name(id);
+        let label = match hotkey {
+            Some(k) => format!("Warp to {name} ({k})"),
+            None => format!("Warp to {name}"),
+        };
+
+        WarpToBonfire { warp: BonfireWarp::new(), id, hotkey, label, logs: Vec::new() }
+    }
+}
+
+impl Widget for WarpToBonfire {
+    fn render(&mut self, ui: &imgui::Ui) {
+        let scale = scaling_factor(ui);
+
+        if ui.button_with_size(&self.label, [BUTTON_WIDTH * scale, BUTTON_HEIGHT]) {
+            warp_to(self.warp.as_ref(), self.id, &mut self.logs);
+        }
+    }
+
+    fn interact(&mut self, ui: &imgui::Ui) {
+        if self.hotkey.map(|k| k.is_pressed(ui)).unwrap_or(false) {
+            warp_to(self.warp.as_ref(), self.id, &mut self.logs);
+        }
+    }
+
+    fn log(&mut self, tx: Sender<String>) {
+        for x in self.logs.drain(..) {
+            tx.send(x).ok();
+        }
+    }
+}
@@ -1,6 +1,7 @@
 import pandas as pd
 import re
 import sys
+import xml.etree.ElementTree as ET
 from glob import glob
 from pathlib import Path
 from itertools import zip_longest
@@ -20,6 +21,29 @@ pub static PARAM_VTABLE: Lazy<HashMap<String, BoxedVisitorLambda>> = Lazy::new(|
     ].into_iter().collect()
 }});'''
 
+PARAM_REF_VTABLE_TEMPLATE = '''
+type BoxedRefVisitorLambda = Box<dyn Fn(*const c_void, &mut dyn ParamRefVisitor) + Send + Sync>;
+
+pub static PARAM_REF_VTABLE: Lazy<HashMap<String, BoxedRefVisitorLambda>> = Lazy::new(|| {{
+    [
+        {vtable_fields}
+    ].into_iter().collect()
+}});'''
+
+PARAM_SIZES_TEMPLATE = '''
+pub static PARAM_SIZES: Lazy<HashMap<String, usize>> = Lazy::new(|| {{
+    [
+        {size_fields}
+    ].into_iter().collect()
+}});'''
+
+PARAM_ENUMS_TEMPLATE = '''
+pub static PARAM_ENUMS: Lazy<HashMap<&'static str, &'static [(i64, &'static str)]>> = Lazy::new(|| {{
+    [
+        {enum_fields}
+    ].into_iter().collect()
+}});'''
+
 STRUCT_TEMPLATE = '''
     #[derive(ParamStruct, Debug)]
     #[repr(C)]
@@ -40,6 +64,61 @@ def to_slug(s):
     return SLUG_RE.sub('', s).lower()
 
 
+def to_rust_str(s):
+    s = str(s).strip().replace('\\', '\\\\').replace('"', '\\"')
+    return '"' + s.replace('\r', '').replace('\n', '\\n') + '"'
+
+
+def is_present(v):
+    return v is not None and not (isinstance(v, float) and pd.isna(v)) and str(v).strip() != ''
+
+
+def read_meta(meta_path):
+    """Reads per-field metadata and enum definitions from a Paramdex Meta file."""
+    fields = {}
+    enums = {}
+
+    # Not every param has a Meta file, but the directory itself must be there,
+    # otherwise every field silently loses its metadata.
+    if not meta_path.parent.is_dir():
+        raise FileNotFoundError(f'Paramdex Meta directory not found: {meta_path.parent}')
+
+    if not meta_path.exists():
+        return fields, enums
+
+    root = ET.parse(meta_path).getroot()
+
+    for field in root.findall('./Field/*'):
+        fields[field.tag] = field.attrib
+
+    for enum in root.findall('./Enums/Enum'):
+        enums[enum.get('Name')] = [
+            (int(option.get('Value')), option.get('Name'))
+            for option in enum.findall('./Option')
+        ]
+
+    return fields, enums
+
+
+def merge_enums(layouts):
+    """Merges the enums of every param into one map, keyed by enum name.
+    Fields only refer to enums by name, so the same name must have the same
+    values in every Meta file that defines it."""
+    enums = {}
+    defined_in = {}
+
+    for l in layouts:
+        for name, options in l.enums.items():
+            if name in enums and enums[name] != options:
+                raise ValueError(
+                    f'Enum {name} has different values in {defined_in[name]} and {l.name}'
+                )
+            enums[name] = options
+            defined_in.setdefault(name, l.name)
+
+    return enums
+
+
 def build_param_layouts(paramdex_path, xtask_path):
     paramdex_path = Path(paramdex_path)
     xtask_path = Path(xtask_path)
@@ -98,17 +177,23 @@ def build_param_layouts(paramdex_path, xtask_path):
     assert(xml_files.keys() == param_names.keys())
 
     return [
-        ParamLayout(name=param_names[i], layout=xml_files[i])
+        ParamLayout(
+            name=param_names[i],
+            layout=xml_files[i],
+            meta=paramdex_path / 'DS3/Meta' / xml_files[i].name
+        )
         for i in param_names.keys()
     ]
 
 
 class ParamLayout:
-    def __init__(self, name, layout):
+    def __init__(self, name, layout, meta):
         self.name = name
         self.name_snake_case = to_snake_case(name)
+        meta_fields, self.enums = read_meta(meta)
         self.fields = ParamLayout.dedup_fields(ParamLayout.group_bitfields([
-            Field(i) for i in pd.read_xml(layout, xpath='./Fields/*')['Def']
+            Field(row['Def'], row, meta_fields)
+            for row in pd.read_xml(layout, xpath='./Fields/*').to_dict('records')
         ]))
 
     def get_struct(self):
@@ -195,7 +280,7 @@ class Field:
         'dummy8': 'u8',
     }
 
-    def __init__(self, definition):
+    def __init__(self, definition, row={}, meta_fields={}):
         if matches := Field.def_array_re.match(definition):
             self.kind = 'array'
             self.name = matches.group(2)
@@ -219,12 +304,43 @@ class Field:
         else:
             raise ValueError(f'Couldn\'t parse: {definition}')
 
+        self.meta = Field.build_meta(row, meta_fields.get(self.name, {}))
+
+    @staticmethod
+    def build_meta(row, meta_field):
+        """Merges the paramdef's field info with the Meta file's, which takes
+        precedence as it is more descriptive."""
+        meta = {}
+
+        for key, row_key, meta_key in [
+            ('display_name', 'DisplayName', 'AltName'),
+            ('description', 'Description', 'Wiki'),
+            ('enum_name', 'Enum', 'Enum'),
+        ]:
+            if is_present(meta_field.get(meta_key)):
+                meta[key] = to_rust_str(meta_field[meta_key])
+            elif is_present(row.get(row_key)):
+                meta[key] = to_rust_str(row[row_key])
+
+        # Numbers are quoted as attributes can't contain negative literals.
+        for key, row_key in [('min', 'Minimum'), ('max', 'Maximum')]:
+            if is_present(row.get(row_key)):
+                meta[key] = to_rust_str(float(row[row_key]))
+
+        return meta
+
     def format(self):
-        return FIELD_TEMPLATE.format(
+        field_tpl = FIELD_TEMPLATE.format(
             field_name=ParamLayout.fix_name(to_snake_case(self.name)),
             field_type=self.type
         )
 
+        if not self.meta or self.kind == 'array':
+            return field_tpl
+
+        meta = ', '.join(f'{key} = {value}' for key, value in self.meta.items())
+        return f'#[field_meta({meta})]\n        ' + field_tpl
+
     def rename(self, idx):
         self.name = self.name + f'_{idx}'
             
@@ -248,6 +364,14 @@ unsafe fn get_lambda<T: ParamStruct>() -> BoxedVisitorLambda {
             r.visit(&mut *v);
         }
     })
+}
+
+unsafe fn get_ref_lambda<T: ParamStruct>() -> BoxedRefVisitorLambda {
+    Box::new(|ptr, v| {
+        if let Some(r) = (ptr as *const T).as_ref() {
+            r.visit_ref(&mut *v);
+        }
+    })
 }''')
 
     print(PARAM_VTABLE_TEMPLATE.format(
@@ -258,5 +382,34 @@ unsafe fn get_lambda<T: ParamStruct>() -> BoxedVisitorLambda {
         )
     ), end='')
 
+    print(PARAM_REF_VTABLE_TEMPLATE.format(
+        vtable_fields='\n        '.join(
+            '''("{param_name}".to_string(), unsafe {{ get_ref_lambda::<{param_name}>() }}),'''
+            .format(param_name=l.name)
+            for l in layouts
+        )
+    ), end='')
+
+    print(PARAM_SIZES_TEMPLATE.format(
+        size_fields='\n        '.join(
+            '''("{param_name}".to_string(), std::mem::size_of::<{param_name}>()),'''
+            .format(param_name=l.name)
+            for l in layouts
+        )
+    ), end='')
+
+    enums = merge_enums(layouts)
+
+    print(PARAM_ENUMS_TEMPLATE.format(
+        enum_fields='\n        '.join(
+            '''({enum_name}, &[{options}][..]),'''
+            .format(
+                enum_name=to_rust_str(name),
+                options=', '.join(f'({value}, {to_rust_str(label)})' for value, label in options)
+            )
+            for name, options in sorted(enums.items())
+        )
+    ), end='')
+
     for l in layouts:
         print(dedent(l.get_struct()), end='')
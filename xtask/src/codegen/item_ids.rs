@@ -0,0 +1,99 @@
+use std::collections::HashMap;
+use std::fs;
+
+use anyhow::{bail, Context};
+use serde_json::{json, Value};
+
+use crate::{project_root, Result};
+
+const CATEGORIES: &str = "xtask/src/codegen/item_list/item_categories.txt";
+const PARAM_NAMES: &str = "lib/libds3/src/params/param_names.json";
+const ITEM_IDS: &str = "practice-tool/src/widgets/item_ids.json";
+
+/// Generates the item spawner's item tree from the categories file, naming
+/// each item after its param row.
+pub(crate) fn codegen() -> Result<()> {
+    let categories =
+        fs::read_to_string(project_root().join(CATEGORIES)).context("item categories")?;
+    let param_names: HashMap<String, HashMap<u64, String>> = serde_json::from_str(
+        &fs::read_to_string(project_root().join(PARAM_NAMES)).context("param names")?,
+    )?;
+
+    let tree = build_tree(&categories, &param_names)?;
+    fs::write(project_root().join(ITEM_IDS), serde_json::to_string(&tree)?)?;
+
+    Ok(())
+}
+
+/// Param holding the row of an item, from the top 4 bits of its id.
+fn item_param(item_id: u32) -> Option<&'static str> {
+    match item_id >> 28 {
+        0x0 => Some("EquipParamWeapon"),
+        0x1 => Some("EquipParamProtector"),
+        0x2 => Some("EquipParamAccessory"),
+        0x4 => Some("EquipParamGoods"),
+        _ => None,
+    }
+}
+
+fn build_tree(
+    categories: &str,
+    param_names: &HashMap<String, HashMap<u64, String>>,
+) -> Result<Vec<Value>> {
+    // Children of every open category, from the root down.
+    let mut stack: Vec<(String, Vec<Value>)> = vec![(String::new(), Vec::new())];
+    let mut errors = Vec::new();
+
+    for (line_no, line) in categories.lines().enumerate() {
+        let content = line.trim();
+        if content.is_empty() || content.starts_with('#') {
+            continue;
+        }
+
+        let indent = line.len() - line.trim_start().len();
+        if indent % 2 != 0 || indent / 2 >= stack.len() {
+            bail!("{CATEGORIES}:{}: wrong indentation", line_no + 1);
+        }
+
+        while stack.len() > indent / 2 + 1 {
+            let (node, children) = stack.pop().unwrap();
+            stack.last_mut().unwrap().1.push(json!({ "node": node, "children": children }));
+        }
+
+        // Items are 8 hex digits, optionally followed by a name; anything else
+        // opens a category.
+        let (id, name_override) = content.split_once(' ').unwrap_or((content, ""));
+        if id.len() != 8 || !id.chars().all(|c| c.is_ascii_hexdigit()) {
+            stack.push((content.to_string(), Vec::new()));
+            continue;
+        }
+        let item_id = u32::from_str_radix(id, 16)?;
+
+        let param_name = item_param(item_id)
+            .and_then(|param| param_names.get(param)?.get(&u64::from(item_id & 0x0FFF_FFFF)));
+
+        let Some(param_name) = param_name else {
+            errors.push(format!("{CATEGORIES}:{}: no param row for item {id}", line_no + 1));
+            continue;
+        };
+
+        // Param names are followed by the Japanese name.
+        let name = match name_override.trim() {
+            "" => param_name.split(" -- ").next().unwrap_or(param_name),
+            name => name,
+        };
+
+        stack.last_mut().unwrap().1.push(json!({ "id": id, "desc": name }));
+    }
+
+    if !errors.is_empty() {
+        bail!("{}", errors.join("\n"));
+    }
+
+    while stack.len() > 1 {
+        let (node, children) = stack.pop().unwrap();
+        stack.last_mut().unwrap().1.push(json!({ "node": node, "children": children }));
+    }
+
+    Ok(stack.pop().unwrap().1)
+}
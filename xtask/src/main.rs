@@ -103,6 +103,7 @@ fn dist_param_mod() -> Result<()> {
         .with_artifact("jdsd_dsiii_param_tinkerer.dll", "param_tinkerer.dll")
         .with_artifact("param_tinkerer.exe", "param_tinkerer.exe")
         .with_artifact("dinput8parammod.dll", "dinput8.dll")
+        .with_artifact("param-mod-lint.exe", "param-mod-lint.exe")
         .with_file("lib/data/PARAM-TINKERER.txt", "README.txt")
         .with_file("lib/param-mod/param-mod.toml", "param-mod.toml")
         .build(&["--locked", "--release", "--workspace", "--exclude", "xtask"])